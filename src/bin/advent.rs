@@ -0,0 +1,159 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-07
+
+//! Run any of the puzzle solutions.
+//!
+//! Usage: advent [--day N|all] [--part P|all] [--input PATH|-] [--input-dir DIR]
+//!
+//! Input defaults to the checked in `input/dayN.txt` files. `--input` reads a single day's
+//! input from a file or stdin while `--input-dir` reads every `dayN.txt` from another directory.
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+use std::time::Instant;
+
+use advent_2018::cli::option_value;
+use advent_2018::input::Source;
+use advent_2018::Error;
+use advent_2018::{day1, day2, day3, day4, day5, day6};
+
+const DAYS: usize = 6;
+const PARTS: usize = 2;
+
+const USAGE: &str = "usage: advent [--day N|all] [--part P|all] [--input PATH|-] [--input-dir DIR]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Selection {
+    All,
+    One(usize),
+}
+
+impl Selection {
+    fn parse(value: &str, max: usize) -> Result<Self, String> {
+        if value == "all" {
+            return Ok(Selection::All);
+        }
+        match value.parse::<usize>() {
+            Ok(v) if v >= 1 && v <= max => Ok(Selection::One(v)),
+            _ => Err(format!("expected 1 to {} or all, got '{}'", max, value)),
+        }
+    }
+
+    fn values(self, max: usize) -> Vec<usize> {
+        match self {
+            Selection::All => (1..=max).collect(),
+            Selection::One(v) => vec![v],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Options {
+    day: Selection,
+    part: Selection,
+    input: Option<String>,
+    input_dir: Option<PathBuf>,
+}
+
+impl Options {
+    /// Input source for the given day.
+    fn source(&self, day: usize) -> Source {
+        match (&self.input, &self.input_dir) {
+            (Some(input), _) => Source::from_arg(Some(input), day),
+            (None, Some(dir)) => Source::File(dir.join(format!("day{}.txt", day))),
+            (None, None) => Source::from_arg(None, day),
+        }
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options {
+        day: Selection::All,
+        part: Selection::All,
+        input: None,
+        input_dir: None,
+    };
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--day" | "--part" | "--input" | "--input-dir" => option_value(&mut args, &arg)?,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        };
+        match arg.as_str() {
+            "--day" => options.day = Selection::parse(&value, DAYS)?,
+            "--part" => options.part = Selection::parse(&value, PARTS)?,
+            "--input" => options.input = Some(value),
+            _ => options.input_dir = Some(PathBuf::from(value)),
+        }
+    }
+    if options.input.is_some() {
+        if options.input_dir.is_some() {
+            return Err("--input and --input-dir are mutually exclusive".to_string());
+        }
+        if options.day == Selection::All {
+            return Err("--input requires a single --day".to_string());
+        }
+    }
+    Ok(options)
+}
+
+/// Solve a single part of a day and return the answer as text.
+fn answer(day: usize, part: usize, input: &str) -> Result<String, Error> {
+    let none = || "none".to_string();
+    let answer = match (day, part) {
+        (1, 1) => day1::part1(input)?.to_string(),
+        (1, 2) => day1::part2(input)?.map_or_else(none, |f| f.to_string()),
+        (2, 1) => day2::part1(input).to_string(),
+        (2, 2) => day2::part2(input)?.unwrap_or_else(none),
+        (3, 1) => day3::part1(input)?.to_string(),
+        (3, 2) => day3::part2(input)?.map_or_else(none, |id| id.to_string()),
+        (4, 1) => day4::part1(input)?.map_or_else(none, |a| a.to_string()),
+        (4, 2) => day4::part2(input)?.map_or_else(none, |a| a.to_string()),
+        (5, 1) => day5::part1(input).to_string(),
+        (5, 2) => day5::part2(input).to_string(),
+        (6, 1) => day6::part1(input)?.to_string(),
+        (6, 2) => day6::part2(input)?.to_string(),
+        _ => unreachable!(),
+    };
+    Ok(answer)
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    for day in options.day.values(DAYS) {
+        let source = options.source(day);
+        let input = source.read().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+        for part in options.part.values(PARTS) {
+            let start = Instant::now();
+            let answer = answer(day, part, &input).unwrap_or_else(|e| {
+                eprintln!("{}", e.in_file(&source));
+                process::exit(1);
+            });
+            let elapsed = start.elapsed();
+            println!("day {} part {}: {} ({:?})", day, part, answer, elapsed);
+        }
+    }
+}
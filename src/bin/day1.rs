@@ -9,42 +9,32 @@
 // Author: Brandon Edens <brandonedens@gmail.com>
 // Date: 2018-12-02
 
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use std::env;
+use std::process;
 
-fn repeat_freq() -> i32 {
-    let file = File::open("input/day1.txt").unwrap();
-    let reader = BufReader::new(&file);
+use advent_2018::day1;
+use advent_2018::input::Source;
+use advent_2018::Result;
 
-    let readings: Vec<i32> = reader
-        .lines()
-        .map(|x| x.unwrap().parse::<i32>().unwrap())
-        .collect();
+fn run(source: &Source) -> Result<()> {
+    let input = source.read()?;
+    println!("freq: {}", day1::part1(&input)?);
 
-    let mut seen: HashSet<i32> = HashSet::new();
-    let mut freq = 0;
-    let it = readings.iter().cycle();
-    for v in it {
-        freq += v;
-        if seen.contains(&freq) {
-            return freq;
-        }
-        seen.insert(freq);
+    let analysis = day1::analyze(&day1::parse(&input)?);
+    match analysis.repeat {
+        Some(r) => println!(
+            "rep freq: {} (step {}, pass {})",
+            r.frequency, r.step, r.pass
+        ),
+        None => println!("rep freq: never (drift {})", analysis.drift),
     }
-
-    0
+    Ok(())
 }
 
 fn main() {
-    let file = File::open("input/day1.txt").unwrap();
-    let reader = BufReader::new(&file);
-    let freq: i32 = reader
-        .lines()
-        .map(|x| x.unwrap().parse::<i32>().unwrap())
-        .sum();
-    println!("freq: {}", freq);
-
-    print!("rep freq: {}", repeat_freq());
+    let source = Source::from_arg(env::args().nth(1).as_deref(), 1);
+    if let Err(e) = run(&source) {
+        eprintln!("{}", e.in_file(&source));
+        process::exit(1);
+    }
 }
@@ -9,66 +9,49 @@
 // Author: Brandon Edens <brandonedens@gmail.com>
 // Date: 2018-12-02
 
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-
-use levenshtein::levenshtein;
-
-fn problem1() {
-    let file = File::open("input/day2.txt").unwrap();
-    let reader = BufReader::new(&file);
-
-    let mut two_sum = 0;
-    let mut three_sum = 0;
-    for line in reader.lines() {
-        let mut map: HashMap<char, usize> = HashMap::new();
-        for c in line.unwrap().chars() {
-            *map.entry(c).or_insert(0) += 1;
-        }
-        for v in map.values() {
-            if *v == 2 {
-                two_sum += 1;
-                break;
-            }
-        }
-        for v in map.values() {
-            if *v == 3 {
-                three_sum += 1;
-                break;
-            }
-        }
+//! Usage: day2 [PATH|-] [K]
+//!
+//! Also reports clusters of box IDs within Hamming distance K (default 1) of each other.
+
+use std::env;
+use std::process;
+
+use advent_2018::day2;
+use advent_2018::input::Source;
+use advent_2018::Result;
+
+fn run(source: &Source, k: usize) -> Result<()> {
+    let input = source.read()?;
+    println!("checksum: {}", day2::part1(&input));
+    for d in day2::near_duplicates(&day2::parse(&input)) {
+        println!("{} {} differ at {}", d.a, d.b, d.index);
+        println!("common: {}", day2::common_chars(d.a, d.b)?);
     }
-    println!("{} * {} = {}", two_sum, three_sum, two_sum * three_sum);
-}
-
-fn problem2() {
-    let file = File::open("input/day2.txt").unwrap();
-    let reader = BufReader::new(&file);
-    let lines: Vec<String> = reader.lines().map(|x| x.unwrap()).collect();
-    for i in 0..lines.len() {
-        for j in (i + 1)..lines.len() {
-            if levenshtein(&lines[i], &lines[j]) == 1 {
-                println!("{} {}", lines[i], lines[j]);
-
-                let a = lines[i].chars();
-                let b = lines[j].chars();
-
-                for (x, y) in a.zip(b) {
-                    if x == y {
-                        print!("{}", x);
-                    }
-                }
-                println!("");
-
-                break;
-            }
+    for cluster in day2::clusters(&day2::parse(&input), k) {
+        if cluster.members.len() > 1 {
+            println!(
+                "cluster of {} within {}: {}",
+                cluster.members.len(),
+                k,
+                cluster.consensus
+            );
         }
     }
+    Ok(())
 }
 
 fn main() {
-    problem1();
-    problem2();
+    let source = Source::from_arg(env::args().nth(1).as_deref(), 2);
+    let k = match env::args().nth(2).map(|k| k.parse::<usize>()) {
+        None => 1,
+        Some(Ok(k)) => k,
+        Some(Err(e)) => {
+            eprintln!("invalid cluster distance: {}", e);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(&source, k) {
+        eprintln!("{}", e.in_file(&source));
+        process::exit(1);
+    }
 }
@@ -9,205 +9,109 @@
 // Author: Brandon Edens <brandonedens@gmail.com>
 // Date: 2018-12-03
 
-#[macro_use]
-extern crate lazy_static;
-
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-
-use regex::Regex;
-
-#[derive(Debug)]
-struct Claim {
-    id: usize,
-    x: usize,
-    y: usize,
-    width: usize,
-    height: usize,
-}
-
-lazy_static! {
-    // Line is: #10 @ 674,274: 25x13
-    static ref RE: Regex =
-        Regex::new(r"^#([0-9]+) @ ([0-9]+),([0-9]+): ([0-9]+)x([0-9]+)$").unwrap();
-}
-
-struct Coord {
-    x: usize,
-    y: usize,
-}
-
-impl Coord {
-    fn new(x: usize, y: usize) -> Self {
-        Coord { x: x, y: y }
-    }
-}
-
-struct Bounds {
-    top_left: Coord,
-    bottom_right: Coord,
-}
-
-impl Bounds {
-    fn new(x1: usize, y1: usize, x2: usize, y2: usize) -> Self {
-        Bounds {
-            top_left: Coord { x: x1, y: y1 },
-            bottom_right: Coord { x: x2, y: y2 },
-        }
-    }
-
-    fn contains(&self, x: usize, y: usize) -> bool {
-        x >= self.top_left.x
-            && x <= self.bottom_right.x
-            && y >= self.top_left.y
-            && y <= self.bottom_right.y
-    }
-}
-
-impl Claim {
-    fn from_line(line: &str) -> Self {
-        let caps = RE.captures(line).unwrap();
-        Claim {
-            id: caps.get(1).unwrap().as_str().parse::<usize>().unwrap(),
-            x: caps.get(2).unwrap().as_str().parse::<usize>().unwrap(),
-            y: caps.get(3).unwrap().as_str().parse::<usize>().unwrap(),
-            width: caps.get(4).unwrap().as_str().parse::<usize>().unwrap(),
-            height: caps.get(5).unwrap().as_str().parse::<usize>().unwrap(),
-        }
-    }
-
-    fn overlaps(&self, other: &Claim) -> bool {
-        let self_bounds = Bounds::new(
-            self.x,
-            self.y,
-            self.x + self.width - 1,
-            self.y + self.height - 1,
-        );
-        let other_bounds = Bounds::new(
-            other.x,
-            other.y,
-            other.x + other.width - 1,
-            other.y + other.height - 1,
-        );
-        self_bounds.contains(other.x, other.y)
-            || self_bounds.contains(other.x, other.y + other.height - 1)
-            || self_bounds.contains(other.x + other.width - 1, other.y)
-            || self_bounds.contains(other.x + other.width - 1, other.y + other.height - 1)
-            || other_bounds.contains(self.x, self.y)
-            || other_bounds.contains(self.x, self.y + self.height - 1)
-            || other_bounds.contains(self.x + self.width - 1, self.y)
-            || other_bounds.contains(self.x + self.width - 1, self.y + self.height - 1)
-    }
+//! Usage: day3 [PATH|-] [--heatmap FILE.ppm] [--report FILE.csv]
+//!        day3 --generate COUNT [--overlapping] [--seed N]
+//!
+//! Optionally writes a heatmap of the claims as a PPM image and a per-claim contention report
+//! as CSV. `--generate` instead writes a random set of claims to stdout for use as a fixture.
+
+use std::env;
+use std::io::{self, BufWriter};
+use std::process;
+
+use advent_2018::cli::{create, option_value};
+use advent_2018::day3;
+use advent_2018::input::Source;
+use advent_2018::Result;
+
+const USAGE: &str = "usage: day3 [PATH|-] [--heatmap FILE.ppm] [--report FILE.csv]
+       day3 --generate COUNT [--overlapping] [--seed N]";
+
+/// Largest side of a generated claim, in line with the puzzle input.
+const GENERATED_MAX_SIDE: usize = 30;
+
+#[derive(Debug, Default)]
+struct Options {
+    input: Option<String>,
+    heatmap: Option<String>,
+    report: Option<String>,
+    generate: Option<usize>,
+    overlapping: bool,
+    seed: u64,
 }
 
-fn problem1() {
-    let file = File::open("input/day3.txt").unwrap();
-    let reader = BufReader::new(&file);
-    let claims = reader
-        .lines()
-        .map(|line| Claim::from_line(&line.unwrap()))
-        .collect::<Vec<Claim>>();
-
-    let mut grid = Box::new([0usize; 1000 * 1000]);
-    for claim in claims {
-        for i in 0..claim.height {
-            for j in 0..claim.width {
-                let y = i + claim.y;
-                let x = j + claim.x;
-                grid[y * 1000 + x] += 1;
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> std::result::Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--heatmap" | "--report" | "--generate" | "--seed" => {
+                let value = option_value(&mut args, &arg)?;
+                let invalid = |_| format!("invalid {} '{}'", arg, value);
+                match arg.as_str() {
+                    "--heatmap" => options.heatmap = Some(value),
+                    "--report" => options.report = Some(value),
+                    "--generate" => options.generate = Some(value.parse().map_err(invalid)?),
+                    _ => options.seed = value.parse().map_err(invalid)?,
+                }
             }
+            "--overlapping" => options.overlapping = true,
+            _ if options.input.is_none() => options.input = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
         }
     }
-
-    let count: usize = grid.iter().filter(|x| **x > 1).count();
-    println!("overlap: {}", count);
+    Ok(options)
 }
 
-fn problem2() {
-    let file = File::open("input/day3.txt").unwrap();
-    let reader = BufReader::new(&file);
-    let claims = reader
-        .lines()
-        .map(|line| Claim::from_line(&line.unwrap()))
-        .collect::<Vec<Claim>>();
-
-    let mut overlaps = vec![false; claims.len()];
-    for i in 0..claims.len() {
-        if overlaps[i] {
-            continue;
-        }
-
-        for j in 0..claims.len() {
-            if i == j {
-                continue;
-            }
-
-            if claims[i].overlaps(&claims[j]) {
-                overlaps[i] = true;
-                overlaps[j] = true;
-                break;
-            }
+fn run(source: &Source, options: &Options) -> Result<()> {
+    let input = source.read()?;
+    println!("overlap: {}", day3::part1(&input)?);
+
+    let claims = day3::parse(&input)?;
+    match day3::Intact::find(&claims) {
+        day3::Intact::None => println!("claim: none"),
+        day3::Intact::One(claim) => println!("claim: #{}", claim.id),
+        day3::Intact::Several(intact) => {
+            let ids: Vec<String> = intact.iter().map(|c| format!("#{}", c.id)).collect();
+            println!("claims: {}", ids.join(" "));
         }
     }
+    let pairs = day3::ClaimIndex::new(&claims).overlapping_pairs();
+    println!("overlapping pairs: {}", pairs.len());
 
-    if let Some((i, _)) = overlaps.iter().enumerate().find(|(i, b)| !*b) {
-        println!("claim: {:#?}", claims[i]);
+    if let Some(path) = &options.heatmap {
+        day3::write_heatmap(&claims, create(path)?).map_err(|e| e.in_file(path))?;
+        println!("heatmap: {}", path);
     }
+    if let Some(path) = &options.report {
+        let report = day3::contention(&claims);
+        day3::write_contention_csv(&report, create(path)?).map_err(|e| e.in_file(path))?;
+        println!("report: {}", path);
+    }
+    Ok(())
 }
 
 fn main() {
-    problem1();
-    problem2();
-}
-
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_overlap() {
-        let c1 = Claim {
-            id: 1,
-            x: 3,
-            y: 4,
-            width: 5,
-            height: 6,
-        };
-        let c2 = Claim {
-            id: 2,
-            x: 10,
-            y: 4,
-            width: 5,
-            height: 6,
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|msg| {
+        eprintln!("{}", msg);
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+    if let Some(count) = options.generate {
+        let layout = if options.overlapping {
+            day3::Layout::Overlapping
+        } else {
+            day3::Layout::Disjoint
         };
-        assert!(!c1.overlaps(&c2));
-        let c2 = Claim {
-            id: 2,
-            x: 7,
-            y: 4,
-            width: 5,
-            height: 6,
-        };
-        assert!(c1.overlaps(&c2));
+        let claims = day3::generate_claims(count, GENERATED_MAX_SIDE, layout, options.seed);
+        if let Err(e) = day3::write_claims(&claims, BufWriter::new(io::stdout())) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
     }
-
-    #[test]
-    fn test_overlap2() {
-        let c1 = Claim {
-            id: 1395,
-            x: 478,
-            y: 475,
-            width: 9,
-            height: 9,
-        };
-        let c2 = Claim {
-            id: 188,
-            x: 907,
-            y: 475,
-            width: 29,
-            height: 14,
-        };
-        assert!(!c1.overlaps(&c2));
-        assert!(!c2.overlaps(&c1));
+    let source = Source::from_arg(options.input.as_deref(), 3);
+    if let Err(e) = run(&source, &options) {
+        eprintln!("{}", e.in_file(&source));
+        process::exit(1);
     }
 }
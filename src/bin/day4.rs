@@ -9,201 +9,122 @@
 // Author: Brandon Edens <brandonedens@gmail.com>
 // Date: 2018-12-05
 
-#[macro_use]
-extern crate lazy_static;
-
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-
-use chrono::prelude::*;
-use chrono::Duration;
-use regex::Regex;
-
-lazy_static! {
-    // Line is: #10 @ 674,274: 25x13
-    static ref RE: Regex =
-        Regex::new(r"^\[(\d+)-(\d+)-(\d+) (\d+):(\d+)\] (.*)$").unwrap();
-
-    static ref BEGINS_SHIFT: Regex =
-        Regex::new(r"^Guard #(\d+) begins shift$").unwrap();
-
-}
-
-#[derive(Debug, PartialOrd, PartialEq, Eq, Ord)]
-enum Behavior {
-    BeginsShift(usize),
-    FallsAsleep,
-    WakesUp,
-}
-
-#[derive(Debug, PartialOrd, PartialEq, Eq, Ord)]
-struct Event {
-    datetime: DateTime<Utc>,
-    behavior: Behavior,
-}
-
-impl Event {
-    fn from_line(line: &str) -> Self {
-        let caps = RE.captures(line).unwrap();
-        let year = caps.get(1).unwrap().as_str().parse::<i32>().unwrap();
-        let month = caps.get(2).unwrap().as_str().parse::<u32>().unwrap();
-        let day = caps.get(3).unwrap().as_str().parse::<u32>().unwrap();
-        let hour = caps.get(4).unwrap().as_str().parse::<u32>().unwrap();
-        let minute = caps.get(5).unwrap().as_str().parse::<u32>().unwrap();
-
-        let datetime = Utc.ymd(year, month, day).and_hms(hour, minute, 0);
-
-        let event = caps.get(6).unwrap().as_str();
-        let behavior = if BEGINS_SHIFT.is_match(event) {
-            let caps = BEGINS_SHIFT.captures(event).unwrap();
-            let id = caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
-            Behavior::BeginsShift(id)
-        } else if event == "falls asleep" {
-            Behavior::FallsAsleep
-        } else if event == "wakes up" {
-            Behavior::WakesUp
-        } else {
-            unreachable!();
-        };
-        Event {
-            datetime: datetime,
-            behavior: behavior,
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Sleep {
-    begin: DateTime<Utc>,
-    end: DateTime<Utc>,
+//! Usage: day4 [PATH|-] [--strategy NAME|all] [--chart] [--colour]
+//!             [--stats-csv FILE.csv] [--stats-json FILE.json]
+//!
+//! Runs the two strategies from the puzzle unless `--strategy` names another one or all of them.
+//! `--chart` also prints the midnight hour of every shift as in the puzzle statement, and the
+//! stats options write per-guard sleep statistics.
+
+use std::env;
+use std::io;
+use std::process;
+
+use advent_2018::cli::{create, option_value};
+use advent_2018::day4::{self, Pick, Strategy};
+use advent_2018::input::Source;
+use advent_2018::Result;
+
+const USAGE: &str = "usage: day4 [PATH|-] [--strategy NAME|all] [--chart] [--colour]
+            [--stats-csv FILE.csv] [--stats-json FILE.json]";
+
+#[derive(Debug, Default)]
+struct Options {
+    input: Option<String>,
+    strategy: Option<String>,
+    chart: bool,
+    colour: bool,
+    stats_csv: Option<String>,
+    stats_json: Option<String>,
 }
 
-/// Read events from the day 4 file.
-fn read_events() -> Vec<Event> {
-    let file = File::open("input/day4.txt").unwrap();
-    let reader = BufReader::new(&file);
-    let mut events = reader
-        .lines()
-        .map(|line| Event::from_line(&line.unwrap()))
-        .collect::<Vec<Event>>();
-    events.sort();
-    events
-}
-
-fn compute_guard_sleep(events: &Vec<Event>) -> HashMap<usize, Vec<Sleep>> {
-    // Convert timeline events into guard sleep information.
-    let mut current_guard = 0;
-    let mut guards: HashMap<usize, Vec<Sleep>> = HashMap::new();
-    let mut it = events.iter();
-    loop {
-        if let Some(event) = it.next() {
-            match event.behavior {
-                Behavior::BeginsShift(id) => {
-                    current_guard = id;
-                    guards.entry(id).or_insert(Vec::new());
-                }
-                Behavior::FallsAsleep => {
-                    assert!(guards.contains_key(&current_guard));
-                    let wake_up_event = it.next().unwrap();
-                    let v = guards.get_mut(&current_guard).unwrap();
-                    v.push(Sleep {
-                        begin: event.datetime,
-                        end: wake_up_event.datetime,
-                    });
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> std::result::Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strategy" => {
+                let name = option_value(&mut args, &arg)?;
+                if name != "all" && day4::strategy(&name).is_none() {
+                    let names: Vec<_> = day4::strategies().iter().map(|s| s.name()).collect();
+                    return Err(format!(
+                        "unknown strategy '{}', expected one of {} or all",
+                        name,
+                        names.join(", ")
+                    ));
                 }
-                _ => {
-                    unreachable!();
+                options.strategy = Some(name);
+            }
+            "--stats-csv" | "--stats-json" => {
+                let value = option_value(&mut args, &arg)?;
+                if arg == "--stats-csv" {
+                    options.stats_csv = Some(value);
+                } else {
+                    options.stats_json = Some(value);
                 }
             }
-        } else {
-            break;
+            "--chart" => options.chart = true,
+            "--colour" => options.colour = true,
+            _ if options.input.is_none() => options.input = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
         }
     }
-
-    guards
+    Ok(options)
 }
 
-fn compute_minute_freq(sleep: &Vec<Sleep>) -> Vec<usize> {
-    let one_minute = Duration::minutes(1);
-    let mut minutes = vec![0; 60];
-    for s in sleep {
-        let mut t = s.begin;
-        loop {
-            minutes[t.time().minute() as usize] += 1;
-
-            t = t + one_minute;
-            if t == s.end {
-                break;
+fn run(source: &Source, options: &Options) -> Result<()> {
+    let input = source.read()?;
+    let strategies: Vec<Box<dyn Strategy>> = match options.strategy.as_deref() {
+        Some("all") => day4::strategies(),
+        Some(name) => day4::strategy(name).into_iter().collect(),
+        None => day4::strategies().into_iter().take(2).collect(),
+    };
+    for strategy in strategies {
+        match day4::choose(&*strategy, &input)? {
+            Pick::None => println!("{}: none", strategy.name()),
+            Pick::One(c) => println!(
+                "{}: {} (guard #{} minute {})",
+                strategy.name(),
+                c.answer(),
+                c.guard,
+                c.minute
+            ),
+            Pick::Several(choices) => {
+                let text: Vec<String> = choices
+                    .iter()
+                    .map(|c| format!("#{} minute {}", c.guard, c.minute))
+                    .collect();
+                println!("{}: tied between {}", strategy.name(), text.join(", "));
             }
         }
     }
-    minutes
-}
-
-fn problem1() {
-    let events = read_events();
-    let guards = compute_guard_sleep(&events);
-
-    // Which guard slept the most?
-    let mut total_sleep: Vec<(usize, usize)> = guards
-        .iter()
-        .map(|(k, v)| {
-            let sum = v
-                .iter()
-                .fold(0, |acc, s| acc + (s.end - s.begin).num_minutes() as usize);
-            (sum, *k)
-        })
-        .collect();
-    total_sleep.sort();
-    let sleepy_guard_id = total_sleep.last().unwrap().1;
 
-    // What minute were they most asleep.
-    let sleepy_guard = guards.get(&sleepy_guard_id).unwrap();
-    let minutes = compute_minute_freq(&sleepy_guard);
-    let mut c: Vec<(usize, usize)> = minutes.iter().enumerate().map(|(e, v)| (*v, e)).collect();
-    c.sort();
-
-    let (_, minute) = c.last().unwrap();
-    println!(
-        "{} * {} = {}",
-        sleepy_guard_id,
-        minute,
-        sleepy_guard_id * minute
-    );
-}
-
-fn problem2() {
-    let events = read_events();
-    let guards = compute_guard_sleep(&events);
-    let guard_minute_freq: Vec<(usize, Vec<usize>)> = guards
-        .iter()
-        .map(|(k, v)| (*k, compute_minute_freq(v)))
-        .collect();
-    let mut max_guard_id = None;
-    let mut max_minute = None;
-    let mut max_sleep = 0;
-    for i in 0..60 {
-        for (guard_id, minutes) in guard_minute_freq.iter() {
-            if minutes[i] > max_sleep {
-                max_sleep = minutes[i];
-                max_guard_id = Some(guard_id);
-                max_minute = Some(i);
-            }
-        }
+    let shifts = day4::shifts(&day4::parse_log(&input)?)?;
+    if let Some(path) = &options.stats_csv {
+        let stats = day4::guard_stats(&shifts);
+        day4::write_guard_stats_csv(&stats, create(path)?).map_err(|e| e.in_file(path))?;
+        println!("stats: {}", path);
+    }
+    if let Some(path) = &options.stats_json {
+        let stats = day4::guard_stats(&shifts);
+        day4::write_guard_stats_json(&stats, create(path)?).map_err(|e| e.in_file(path))?;
+        println!("stats: {}", path);
     }
-    let max_guard_id = max_guard_id.unwrap();
-    let max_minute = max_minute.unwrap();
-    println!(
-        "{} * {} = {}",
-        max_guard_id,
-        max_minute,
-        max_guard_id * max_minute
-    );
+    if options.chart {
+        println!();
+        day4::write_sleep_chart(&shifts, options.colour, io::stdout().lock())?;
+    }
+    Ok(())
 }
 
 fn main() {
-    problem1();
-    problem2();
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|msg| {
+        eprintln!("{}", msg);
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+    let source = Source::from_arg(options.input.as_deref(), 4);
+    if let Err(e) = run(&source, &options) {
+        eprintln!("{}", e.in_file(&source));
+        process::exit(1);
+    }
 }
@@ -9,84 +9,24 @@
 // Author: Brandon Edens <brandonedens@gmail.com>
 // Date: 2018-12-06
 
-use std::fs::File;
-use std::io::prelude::*;
-
-fn problem1() {
-    let file = File::open("input/day5.txt").unwrap();
-
-    let mut v: Vec<char> = Vec::new();
-    for c in file.bytes() {
-        let c = c.unwrap() as char;
-
-        if !c.is_ascii_alphabetic() {
-            continue;
-        }
-
-        if v.len() == 0 {
-            v.push(c);
-            continue;
-        }
-
-        let last = v.last().unwrap();
-        if c.eq_ignore_ascii_case(last)
-            && ((c.is_ascii_uppercase() && last.is_ascii_lowercase())
-                || (last.is_ascii_uppercase() && c.is_ascii_lowercase()))
-        {
-            // We have aA or Aa.
-            v.pop();
-            continue;
-        }
-
-        v.push(c);
-    }
-    println!("len: {}", v.len());
-}
-
-fn problem2() {
-    let alphabet = "abcdefghijklmnopqrstuvwxyz";
-    let mut counts: Vec<(usize, char)> = Vec::new();
-
-    for a in alphabet.chars() {
-        let mut v: Vec<char> = Vec::new();
-
-        let file = File::open("input/day5.txt").unwrap();
-        for c in file.bytes() {
-            let c = c.unwrap() as char;
-
-            if !c.is_ascii_alphabetic() {
-                continue;
-            }
-
-            if c.eq_ignore_ascii_case(&a) {
-                continue;
-            }
-
-            if v.len() == 0 {
-                v.push(c);
-                continue;
-            }
-
-            let last = v.last().unwrap();
-            if c.eq_ignore_ascii_case(last)
-                && ((c.is_ascii_uppercase() && last.is_ascii_lowercase())
-                    || (last.is_ascii_uppercase() && c.is_ascii_lowercase()))
-            {
-                // We have aA or Aa.
-                v.pop();
-                continue;
-            }
-
-            v.push(c);
-        }
-        counts.push((v.len(), a));
-    }
-    counts.sort();
-
-    println!("{:?}", counts.first().unwrap());
+use std::env;
+use std::process;
+
+use advent_2018::day5;
+use advent_2018::input::Source;
+use advent_2018::Result;
+
+fn run(source: &Source) -> Result<()> {
+    let input = source.read()?;
+    println!("len: {}", day5::part1(&input));
+    println!("shortest len: {}", day5::part2(&input));
+    Ok(())
 }
 
 fn main() {
-    problem1();
-    problem2();
+    let source = Source::from_arg(env::args().nth(1).as_deref(), 5);
+    if let Err(e) = run(&source) {
+        eprintln!("{}", e.in_file(&source));
+        process::exit(1);
+    }
 }
@@ -0,0 +1,33 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-07
+
+//! Helpers shared by the command line binaries.
+
+use std::fs::File;
+use std::io::BufWriter;
+
+use crate::error::{Error, Result};
+
+/// Take the value following a command line option.
+pub fn option_value<I: Iterator<Item = String>>(
+    args: &mut I,
+    option: &str,
+) -> std::result::Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for {}", option))
+}
+
+/// Create an output file, attaching its path to any error.
+pub fn create(path: &str) -> Result<BufWriter<File>> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|e| Error::from(e).in_file(path))
+}
@@ -0,0 +1,282 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-02
+
+//! Day 1: Chronal Calibration.
+
+use std::collections::HashSet;
+
+use crate::error::{parse_int, parse_lines, Result};
+
+/// Parse the frequency change readings, one signed integer per line.
+pub fn parse(input: &str) -> Result<Vec<i32>> {
+    parse_lines(input, |line| parse_int(line, line))
+}
+
+/// Frequency that is reached for a second time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    pub frequency: i64,
+    /// Number of readings applied when the frequency was reached again.
+    pub step: usize,
+    /// Pass through the readings, starting from 0, during which the repeat occurred.
+    pub pass: usize,
+}
+
+/// Summary of how the frequency evolves as the readings are applied over and over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Change in frequency over one full pass of the readings.
+    pub drift: i64,
+    /// First repeated frequency or `None` if no frequency is ever reached twice.
+    pub repeat: Option<Repeat>,
+}
+
+/// Frequencies reached before each reading of a pass is applied, starting from 0.
+fn prefix_sums(readings: &[i32]) -> impl Iterator<Item = i64> + '_ {
+    readings.iter().scan(0i64, |freq, v| {
+        let before = *freq;
+        *freq += i64::from(*v);
+        Some(before)
+    })
+}
+
+/// Determine whether any frequency is ever reached twice.
+///
+/// After `k` full passes and `i` further readings the frequency is `prefix[i] + k * drift`, so
+/// two positions in the pass can only ever produce the same frequency when their prefix sums are
+/// congruent modulo the drift. Without drift the frequencies cycle and the start is revisited.
+pub fn repeats(readings: &[i32], drift: i64) -> bool {
+    if readings.is_empty() {
+        return false;
+    }
+    if drift == 0 {
+        return true;
+    }
+    let mut residues: HashSet<i64> = HashSet::new();
+    !prefix_sums(readings).all(|s| residues.insert(s.rem_euclid(drift)))
+}
+
+/// Return the first frequency reached twice while repeatedly applying the readings.
+///
+/// Readings are applied one at a time so this is only called once [`repeats`] guarantees that it
+/// will terminate.
+fn repeat_freq(readings: &[i32]) -> Repeat {
+    let mut freq = 0;
+    // The starting frequency counts as having been reached.
+    let mut seen: HashSet<i64> = HashSet::new();
+    seen.insert(freq);
+    let it = readings.iter().cycle().enumerate();
+    for (e, v) in it {
+        freq += i64::from(*v);
+        if !seen.insert(freq) {
+            return Repeat {
+                frequency: freq,
+                step: e + 1,
+                pass: e / readings.len(),
+            };
+        }
+    }
+
+    unreachable!("readings never repeat");
+}
+
+/// Find the first repeated frequency by grouping the prefix sums by residue modulo the drift.
+///
+/// Within a residue class every prefix sum climbs by the drift each pass until it lands on the
+/// next larger prefix sum of the class, so only neighbours in sorted order need to be compared.
+/// Equal neighbours repeat within the first pass.
+fn residue_repeat(readings: &[i32], drift: i64) -> Option<Repeat> {
+    let n = readings.len();
+    if n == 0 {
+        return None;
+    }
+
+    // Work with a positive drift so that frequencies only ever climb.
+    let sign = if drift < 0 { -1 } else { 1 };
+    let drift = drift * sign;
+    let mut sums: Vec<(i64, i64, usize)> = prefix_sums(readings)
+        .enumerate()
+        .map(|(i, s)| {
+            let s = s * sign;
+            let residue = if drift == 0 { 0 } else { s.rem_euclid(drift) };
+            (residue, s, i)
+        })
+        .collect();
+    sums.sort();
+
+    let first = sums
+        .windows(2)
+        .filter_map(|w| {
+            let (r1, s1, i1) = w[0];
+            let (r2, s2, i2) = w[1];
+            if r1 != r2 {
+                None
+            } else if s1 == s2 {
+                Some((i2, s2))
+            } else if drift == 0 {
+                None
+            } else {
+                let passes = ((s2 - s1) / drift) as usize;
+                Some((passes * n + i1, s2))
+            }
+        })
+        .min()
+        // Without drift and no repeat within the pass the frequency returns to 0.
+        .or(if drift == 0 { Some((n, 0)) } else { None });
+
+    first.map(|(step, frequency)| Repeat {
+        frequency: frequency * sign,
+        step,
+        pass: (step - 1) / n,
+    })
+}
+
+/// Approach used to find the first repeated frequency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Apply the readings one at a time remembering every frequency seen. Time and memory grow
+    /// with the number of passes before the repeat.
+    BruteForce,
+    /// Sort the prefix sums by residue modulo the drift. Runs in O(n log n) however many passes
+    /// the repeat takes.
+    Residue,
+}
+
+/// Analyze the readings, reporting the first repeated frequency if there is one.
+pub fn analyze(readings: &[i32]) -> Analysis {
+    analyze_with(readings, Method::Residue)
+}
+
+/// Analyze the readings using the given method to find the repeated frequency.
+pub fn analyze_with(readings: &[i32], method: Method) -> Analysis {
+    let drift = readings.iter().map(|v| i64::from(*v)).sum();
+    let repeat = match method {
+        Method::BruteForce if repeats(readings, drift) => Some(repeat_freq(readings)),
+        Method::BruteForce => None,
+        Method::Residue => residue_repeat(readings, drift),
+    };
+    Analysis { drift, repeat }
+}
+
+/// Resulting frequency after applying every reading once.
+pub fn part1(input: &str) -> Result<i32> {
+    Ok(parse(input)?.iter().sum())
+}
+
+/// First frequency that is reached twice, if any.
+pub fn part2(input: &str) -> Result<Option<i64>> {
+    Ok(analyze(&parse(input)?).repeat.map(|r| r.frequency))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::input::Source;
+
+    #[test]
+    fn test_part1_examples() {
+        assert_eq!(part1("+1\n-2\n+3\n+1\n").unwrap(), 3);
+        assert_eq!(part1("+1\n+1\n+1\n").unwrap(), 3);
+        assert_eq!(part1("+1\n+1\n-2\n").unwrap(), 0);
+        assert_eq!(part1("-1\n-2\n-3\n").unwrap(), -6);
+    }
+
+    #[test]
+    fn test_part2_examples() {
+        assert_eq!(part2("+1\n-2\n+3\n+1\n").unwrap(), Some(2));
+        assert_eq!(part2("+1\n-1\n").unwrap(), Some(0));
+        assert_eq!(part2("+3\n+3\n+4\n-2\n-4\n").unwrap(), Some(10));
+        assert_eq!(part2("-6\n+3\n+8\n+5\n-6\n").unwrap(), Some(5));
+        assert_eq!(part2("+7\n+7\n-2\n-7\n-4\n").unwrap(), Some(14));
+    }
+
+    #[test]
+    fn test_analyze_repeat() {
+        let analysis = analyze(&[3, 3, 4, -2, -4]);
+        assert_eq!(analysis.drift, 4);
+        assert_eq!(
+            analysis.repeat,
+            Some(Repeat {
+                frequency: 10,
+                step: 7,
+                pass: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_analyze_never_repeats() {
+        let analysis = analyze(&[1, 1]);
+        assert_eq!(analysis.drift, 2);
+        assert_eq!(analysis.repeat, None);
+        assert_eq!(analyze(&[5]).repeat, None);
+        assert_eq!(analyze(&[]).repeat, None);
+    }
+
+    #[test]
+    fn test_methods_agree() {
+        let cases: &[&[i32]] = &[
+            &[1, -2, 3, 1],
+            &[1, -1],
+            &[3, 3, 4, -2, -4],
+            &[-6, 3, 8, 5, -6],
+            &[7, 7, -2, -7, -4],
+            &[-3, -3, -4, 2, 4],
+            &[4, -7, 2, 9, -5, -1],
+            &[0],
+            &[2, -2, 2, -2],
+            &[1, 1],
+            &[],
+        ];
+        for readings in cases {
+            assert_eq!(
+                analyze_with(readings, Method::Residue),
+                analyze_with(readings, Method::BruteForce),
+                "{:?}",
+                readings
+            );
+        }
+    }
+
+    #[test]
+    fn test_residue_large_offsets() {
+        // Brute force would need ten million passes to find this.
+        let analysis = analyze_with(&[10_000_000, -9_999_999], Method::Residue);
+        assert_eq!(
+            analysis.repeat,
+            Some(Repeat {
+                frequency: 10_000_000,
+                step: 20_000_000,
+                pass: 9_999_999,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let e = parse("+1\nfoo\n").unwrap_err();
+        assert_eq!(e.line, Some(2));
+        assert_eq!(e.text, "foo");
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Source::from_arg(None, 1).read().unwrap();
+        assert_eq!(part1(&input).unwrap(), 547);
+        assert_eq!(part2(&input).unwrap(), Some(76414));
+        let readings = parse(&input).unwrap();
+        assert_eq!(
+            analyze_with(&readings, Method::BruteForce),
+            analyze_with(&readings, Method::Residue)
+        );
+    }
+}
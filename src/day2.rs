@@ -0,0 +1,369 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-02
+
+//! Day 2: Inventory Management System.
+//!
+//! IDs are compared as NFC normalized extended grapheme clusters so that letters built from
+//! combining marks count as a single position no matter how they were encoded.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::error::{Error, ErrorKind, Result};
+
+/// Parse the box IDs, one per line, normalizing each to NFC.
+pub fn parse(input: &str) -> Vec<String> {
+    input.lines().map(|line| line.nfc().collect()).collect()
+}
+
+/// Split an ID into the user perceived letters that are compared.
+fn letters(id: &str) -> Vec<&str> {
+    id.graphemes(true).collect()
+}
+
+/// Letters of a single ID that occur one of the requested number of times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakdown<'a> {
+    pub id: &'a str,
+    /// Sorted letters keyed by how many times they occur. Multiplicities that no letter
+    /// satisfies are left out.
+    pub letters: BTreeMap<usize, Vec<&'a str>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum<'a> {
+    /// Number of IDs with some letter that occurs exactly `k` times, keyed by `k`.
+    pub counts: BTreeMap<usize, usize>,
+    /// Product of the counts.
+    pub product: usize,
+    /// Breakdown for every ID in input order.
+    pub breakdown: Vec<Breakdown<'a>>,
+}
+
+/// Compute the checksum over an arbitrary set of multiplicities.
+pub fn checksum_with<'a, S: AsRef<str>>(ids: &'a [S], multiplicities: &[usize]) -> Checksum<'a> {
+    let mut counts: BTreeMap<usize, usize> = multiplicities.iter().map(|k| (*k, 0)).collect();
+    let mut breakdown = Vec::with_capacity(ids.len());
+    for id in ids {
+        let id = id.as_ref();
+        let mut map: HashMap<&str, usize> = HashMap::new();
+        for c in letters(id) {
+            *map.entry(c).or_insert(0) += 1;
+        }
+
+        let mut letters: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+        for (c, v) in map {
+            if counts.contains_key(&v) {
+                letters.entry(v).or_default().push(c);
+            }
+        }
+        for (k, v) in letters.iter_mut() {
+            v.sort();
+            *counts.get_mut(k).unwrap() += 1;
+        }
+        breakdown.push(Breakdown { id, letters });
+    }
+
+    let product = counts.values().product();
+    Checksum {
+        counts,
+        product,
+        breakdown,
+    }
+}
+
+/// Count the IDs that contain some letter exactly twice and exactly three times and multiply
+/// the two counts together.
+pub fn checksum<S: AsRef<str>>(ids: &[S]) -> usize {
+    checksum_with(ids, &[2, 3]).product
+}
+
+/// Split two IDs into letters, failing if they don't have the same number of letters.
+fn paired_letters<'a, 'b>(a: &'a str, b: &'b str) -> Result<(Vec<&'a str>, Vec<&'b str>)> {
+    let x = letters(a);
+    let y = letters(b);
+    if x.len() != y.len() {
+        let text = format!("{} {}", a, b);
+        return Err(Error::new(
+            ErrorKind::LengthMismatch(x.len(), y.len()),
+            &text,
+        ));
+    }
+    Ok((x, y))
+}
+
+/// Return the letters that two IDs of the same length share at the same position.
+pub fn common_chars(a: &str, b: &str) -> Result<String> {
+    let (x, y) = paired_letters(a, b)?;
+    Ok(x.iter()
+        .zip(y)
+        .filter(|(x, y)| **x == *y)
+        .map(|(x, _)| *x)
+        .collect())
+}
+
+/// Pair of IDs that differ in exactly one position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearDuplicate<'a> {
+    pub a: &'a str,
+    pub b: &'a str,
+    /// Letter index at which the two IDs differ.
+    pub index: usize,
+}
+
+/// Find every pair of IDs that differ in exactly one position.
+///
+/// For each position the IDs are bucketed by their letters with that position removed. IDs
+/// sharing a bucket have the same length and are equal everywhere else, so any two distinct IDs
+/// within a bucket differ at exactly that position. Only one position's buckets are held at a
+/// time so memory stays proportional to the input. Pairs are ordered by where the first ID
+/// appears in the input.
+pub fn near_duplicates<S: AsRef<str>>(ids: &[S]) -> Vec<NearDuplicate<'_>> {
+    let chars: Vec<Vec<&str>> = ids.iter().map(|id| letters(id.as_ref())).collect();
+    let max_len = chars.iter().map(|c| c.len()).max().unwrap_or(0);
+
+    let mut pairs: Vec<(usize, usize, usize)> = Vec::new();
+    for index in 0..max_len {
+        let mut buckets: HashMap<String, Vec<usize>> = HashMap::new();
+        for (e, c) in chars.iter().enumerate().filter(|(_, c)| index < c.len()) {
+            // Separate the letters so IDs of different lengths can never share a key.
+            let key = [&c[..index], &c[index + 1..]].concat().join("\0");
+            buckets.entry(key).or_default().push(e);
+        }
+        for bucket in buckets.values().filter(|b| b.len() > 1) {
+            for (n, &i) in bucket.iter().enumerate() {
+                for &j in &bucket[n + 1..] {
+                    // Identical IDs land in the same bucket for every position.
+                    if chars[i][index] != chars[j][index] {
+                        pairs.push((i, j, index));
+                    }
+                }
+            }
+        }
+    }
+    pairs.sort();
+
+    pairs
+        .into_iter()
+        .map(|(i, j, index)| NearDuplicate {
+            a: ids[i].as_ref(),
+            b: ids[j].as_ref(),
+            index,
+        })
+        .collect()
+}
+
+/// Number of positions at which two IDs of the same length differ.
+pub fn hamming_distance(a: &str, b: &str) -> Result<usize> {
+    let (x, y) = paired_letters(a, b)?;
+    Ok(x.iter().zip(y).filter(|(x, y)| **x != *y).count())
+}
+
+/// IDs that are all within the clustering distance of one another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster<'a> {
+    /// Members in input order.
+    pub members: Vec<&'a str>,
+    /// Letters that every member shares at the same position.
+    pub consensus: String,
+}
+
+/// Letters at the positions where every member agrees.
+fn consensus(members: &[&str]) -> String {
+    let first = letters(members[0]);
+    let mut agree = vec![true; first.len()];
+    for id in &members[1..] {
+        for (e, c) in letters(id).into_iter().enumerate().take(first.len()) {
+            if first[e] != c {
+                agree[e] = false;
+            }
+        }
+    }
+    first
+        .iter()
+        .zip(agree)
+        .filter(|(_, a)| *a)
+        .map(|(c, _)| *c)
+        .collect()
+}
+
+/// Group the IDs into clusters where every pair of members is within Hamming distance `k` of
+/// each other, so only IDs of the same length share a cluster. Taking the IDs in input order,
+/// each joins the first cluster it is close enough to every member of, or else starts a new one.
+/// Every ID belongs to exactly one cluster, so isolated IDs form clusters of one. Clusters are
+/// ordered largest first and then by where they first appear in the input.
+pub fn clusters<S: AsRef<str>>(ids: &[S], k: usize) -> Vec<Cluster<'_>> {
+    let mut groups: Vec<Vec<&str>> = Vec::new();
+    for id in ids.iter().map(|id| id.as_ref()) {
+        let close = |member: &&str| hamming_distance(id, member).is_ok_and(|d| d <= k);
+        match groups.iter_mut().find(|g| g.iter().all(close)) {
+            Some(group) => group.push(id),
+            None => groups.push(vec![id]),
+        }
+    }
+    // Stable sort keeps clusters of equal size in input order.
+    groups.sort_by_key(|g| Reverse(g.len()));
+
+    groups
+        .into_iter()
+        .map(|members| Cluster {
+            consensus: consensus(&members),
+            members,
+        })
+        .collect()
+}
+
+pub fn part1(input: &str) -> usize {
+    checksum(&parse(input))
+}
+
+/// Common letters between the two correct box IDs.
+pub fn part2(input: &str) -> Result<Option<String>> {
+    near_duplicates(&parse(input))
+        .first()
+        .map(|d| common_chars(d.a, d.b))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::input::Source;
+
+    #[test]
+    fn test_part1_example() {
+        let input = "abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab\n";
+        assert_eq!(part1(input), 12);
+    }
+
+    #[test]
+    fn test_checksum_with() {
+        let ids = parse("abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab\naaaabb\n");
+        let checksum = checksum_with(&ids, &[2, 3, 4]);
+        let counts: Vec<(usize, usize)> = checksum.counts.into_iter().collect();
+        assert_eq!(counts, vec![(2, 5), (3, 3), (4, 1)]);
+        assert_eq!(checksum.product, 15);
+
+        assert!(checksum.breakdown[0].letters.is_empty());
+        let bababc = &checksum.breakdown[1];
+        assert_eq!(bababc.id, "bababc");
+        assert_eq!(bababc.letters[&2], vec!["a"]);
+        assert_eq!(bababc.letters[&3], vec!["b"]);
+        assert_eq!(checksum.breakdown[4].letters[&2], vec!["a", "d"]);
+        assert_eq!(checksum.breakdown[7].letters[&4], vec!["a"]);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz\n";
+        assert_eq!(part2(input).unwrap(), Some("fgij".to_string()));
+    }
+
+    #[test]
+    fn test_near_duplicates() {
+        let ids = ["abcd", "abxd", "abyd", "abcd", "zbcd", "abc", "abcde"];
+        let found = near_duplicates(&ids);
+        let pairs: Vec<(&str, &str, usize)> = found.iter().map(|d| (d.a, d.b, d.index)).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("abcd", "abxd", 2),
+                ("abcd", "abyd", 2),
+                ("abcd", "zbcd", 0),
+                ("abxd", "abyd", 2),
+                ("abxd", "abcd", 2),
+                ("abyd", "abcd", 2),
+                ("abcd", "zbcd", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance("abcde", "axcye").unwrap(), 2);
+        assert_eq!(hamming_distance("abcde", "abcde").unwrap(), 0);
+        assert!(hamming_distance("abcde", "abcd").is_err());
+    }
+
+    #[test]
+    fn test_clusters() {
+        let ids = parse("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz\n");
+        let found = clusters(&ids, 1);
+        assert_eq!(found.len(), 6);
+        assert_eq!(found[0].members, vec!["fghij", "fguij"]);
+        assert_eq!(found[0].consensus, "fgij");
+        assert_eq!(found[1].members, vec!["abcde"]);
+        assert_eq!(found[1].consensus, "abcde");
+
+        let found = clusters(&ids, 2);
+        assert_eq!(found[0].members, vec!["abcde", "axcye"]);
+        assert_eq!(found[0].consensus, "ace");
+        assert_eq!(found[1].members, vec!["fghij", "fguij"]);
+    }
+
+    #[test]
+    fn test_clusters_pairwise() {
+        // abaa is close to both aaaa and abba, but those two are too far apart to share a cluster.
+        let found = clusters(&["aaaa", "abba", "zzzz", "abaa"], 1);
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].members, vec!["aaaa", "abaa"]);
+        assert_eq!(found[0].consensus, "aaa");
+        assert_eq!(found[1].members, vec!["abba"]);
+        assert_eq!(found[2].members, vec!["zzzz"]);
+
+        let found = clusters(&["aaaa", "abba", "zzzz", "abaa"], 2);
+        assert_eq!(found[0].members, vec!["aaaa", "abba", "abaa"]);
+    }
+
+    #[test]
+    fn test_unicode_normalization() {
+        // Decomposed and precomposed forms of the same IDs compare equal after parsing.
+        let ids = parse("cafe\u{301}x\ncaf\u{e9}y\n");
+        assert_eq!(ids[0], "caf\u{e9}x");
+        let found = near_duplicates(&ids);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].index, 4);
+        assert_eq!(common_chars(found[0].a, found[0].b).unwrap(), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_grapheme_letters() {
+        // A letter with a combining mark that has no precomposed form is still one letter.
+        let ids = parse("aq\u{307}q\u{307}\naq\u{307}b\n");
+        assert_eq!(hamming_distance(&ids[0], &ids[1]).unwrap(), 1);
+        let checksum = checksum_with(&ids, &[2]);
+        assert_eq!(checksum.breakdown[0].letters[&2], vec!["q\u{307}"]);
+        assert!(checksum.breakdown[1].letters.is_empty());
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let e = common_chars("abcde", "abcd").unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::LengthMismatch(5, 4)));
+        assert_eq!(e.text, "abcde abcd");
+        assert!(near_duplicates(&["abcd", "abc", "abcde"]).is_empty());
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Source::from_arg(None, 2).read().unwrap();
+        assert_eq!(part1(&input), 7657);
+        assert_eq!(
+            part2(&input).unwrap(),
+            Some("ivjhcadokeltwgsfsmqwrbnuy".to_string())
+        );
+        assert_eq!(near_duplicates(&parse(&input)).len(), 1);
+    }
+}
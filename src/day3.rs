@@ -0,0 +1,974 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-03
+
+//! Day 3: No Matter How You Slice It.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::ops::Range;
+use std::ptr;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::{parse_int, parse_lines, Error, ErrorKind, Result};
+use crate::geometry::{Bounds, Coord};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub id: usize,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+lazy_static! {
+    // Line is: #10 @ 674,274: 25x13
+    static ref RE: Regex =
+        Regex::new(r"^#([0-9]+) @ ([0-9]+),([0-9]+): ([0-9]+)x([0-9]+)$").unwrap();
+}
+
+impl FromStr for Claim {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let caps = RE
+            .captures(line)
+            .ok_or_else(|| Error::new(ErrorKind::Syntax("#id @ x,y: wxh"), line))?;
+        let claim = Claim {
+            id: parse_int(&caps[1], line)?,
+            x: parse_int(&caps[2], line)?,
+            y: parse_int(&caps[3], line)?,
+            width: parse_int(&caps[4], line)?,
+            height: parse_int(&caps[5], line)?,
+        };
+        // Edges are computed as position plus size throughout so they must fit.
+        if claim.x.checked_add(claim.width).is_none() || claim.y.checked_add(claim.height).is_none()
+        {
+            return Err(Error::new(ErrorKind::Overflow, line));
+        }
+        Ok(claim)
+    }
+}
+
+impl fmt::Display for Claim {
+    /// Format the claim the same way it appears in the puzzle input.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} @ {},{}: {}x{}",
+            self.id, self.x, self.y, self.width, self.height
+        )
+    }
+}
+
+impl Claim {
+    /// Square inches covered by the claim or `None` if the claim is empty.
+    pub fn bounds(&self) -> Option<Bounds> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        Some(Bounds::new(
+            self.x,
+            self.y,
+            self.x + self.width - 1,
+            self.y + self.height - 1,
+        ))
+    }
+
+    /// Rectangle of fabric claimed by both claims.
+    pub fn intersection(&self, other: &Claim) -> Option<Bounds> {
+        self.bounds()?.intersection(&other.bounds()?)
+    }
+
+    pub fn overlaps(&self, other: &Claim) -> bool {
+        self.intersection(other).is_some()
+    }
+}
+
+/// Parse the claims, one per line.
+pub fn parse(input: &str) -> Result<Vec<Claim>> {
+    parse_lines(input, str::parse)
+}
+
+/// Count the square inches of fabric that are within two or more claims.
+///
+/// A vertical line is swept across the left and right edges of the claims. Between two
+/// consecutive edges the claims crossing the line don't change, so the contested area of that
+/// slab is its width multiplied by the contested length along the line. The y axis is compressed
+/// down to the claims' top and bottom edges which keeps memory proportional to the number of
+/// claims rather than the size of the fabric.
+pub fn overlap_area(claims: &[Claim]) -> usize {
+    let mut ys: Vec<usize> = claims
+        .iter()
+        .flat_map(|c| vec![c.y, c.y + c.height])
+        .collect();
+    ys.sort();
+    ys.dedup();
+
+    // Claims enter the sweep at their left edge and leave at their right edge.
+    let mut events: Vec<(usize, isize, usize)> = claims
+        .iter()
+        .enumerate()
+        .flat_map(|(e, c)| vec![(c.x, 1, e), (c.x + c.width, -1, e)])
+        .collect();
+    events.sort();
+
+    // Number of claims covering each compressed y segment at the current sweep position.
+    let mut counts = vec![0isize; ys.len().saturating_sub(1)];
+    let mut area = 0;
+    let mut prev_x = 0;
+    for (x, delta, e) in events {
+        if x > prev_x {
+            let contested: usize = counts
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 1)
+                .map(|(k, _)| ys[k + 1] - ys[k])
+                .sum();
+            area += (x - prev_x) * contested;
+        }
+        prev_x = x;
+
+        let claim = &claims[e];
+        let top = ys.binary_search(&claim.y).unwrap();
+        let bottom = ys.binary_search(&(claim.y + claim.height)).unwrap();
+        for count in &mut counts[top..bottom] {
+            *count += delta;
+        }
+    }
+
+    area
+}
+
+/// Pair of overlapping claims.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap<'a> {
+    pub a: &'a Claim,
+    pub b: &'a Claim,
+    /// Fabric claimed by both.
+    pub shared: Bounds,
+}
+
+/// Uniform grid of buckets over the fabric where each bucket lists the claims touching it.
+///
+/// Only occupied buckets are stored so memory depends on the claims rather than the size of the
+/// fabric. Queries only test the claims found in the buckets that the query touches, or every
+/// occupied bucket when the query covers more cells than there are buckets. Claims covering more
+/// than `MAX_CLAIM_CELLS` cells are kept aside and tested by every query instead of being spread
+/// over the grid.
+#[derive(Debug)]
+pub struct ClaimIndex<'a> {
+    claims: &'a [Claim],
+    cell_size: usize,
+    buckets: HashMap<(usize, usize), Vec<usize>>,
+    wide: Vec<usize>,
+}
+
+/// Most grid cells a claim may be bucketed into before it is kept aside as a wide claim.
+const MAX_CLAIM_CELLS: usize = 64;
+
+/// Inclusive range of grid cells along each axis.
+#[derive(Debug, Clone, Copy)]
+struct CellRange {
+    x: (usize, usize),
+    y: (usize, usize),
+}
+
+impl CellRange {
+    /// Number of cells in the range, saturating for ranges spanning the whole coordinate space.
+    fn len(&self) -> usize {
+        let side = |(lo, hi): (usize, usize)| (hi - lo).saturating_add(1);
+        side(self.x).saturating_mul(side(self.y))
+    }
+
+    fn contains(&self, (x, y): (usize, usize)) -> bool {
+        self.x.0 <= x && x <= self.x.1 && self.y.0 <= y && y <= self.y.1
+    }
+
+    fn cells(self) -> impl Iterator<Item = (usize, usize)> {
+        let (x0, x1) = self.x;
+        (self.y.0..=self.y.1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+    }
+}
+
+impl<'a> ClaimIndex<'a> {
+    /// Index the claims using cells about the size of an average claim. With cells that size a
+    /// typical claim lands in a handful of buckets; the few that are much larger than the
+    /// average are kept aside as wide claims so they cannot flood the grid.
+    pub fn new(claims: &'a [Claim]) -> Self {
+        let sides: usize = claims.iter().map(|c| c.width.max(c.height)).sum();
+        let cell_size = (sides / claims.len().max(1)).max(1);
+
+        let mut index = ClaimIndex {
+            claims,
+            cell_size,
+            buckets: HashMap::new(),
+            wide: Vec::new(),
+        };
+        for (e, claim) in claims.iter().enumerate() {
+            if let Some(bounds) = claim.bounds() {
+                let range = index.cells(&bounds);
+                if range.len() > MAX_CLAIM_CELLS {
+                    index.wide.push(e);
+                    continue;
+                }
+                for cell in range.cells() {
+                    index.buckets.entry(cell).or_default().push(e);
+                }
+            }
+        }
+        index
+    }
+
+    /// Grid cells that the bounds touch.
+    fn cells(&self, bounds: &Bounds) -> CellRange {
+        CellRange {
+            x: (
+                bounds.top_left.x / self.cell_size,
+                bounds.bottom_right.x / self.cell_size,
+            ),
+            y: (
+                bounds.top_left.y / self.cell_size,
+                bounds.bottom_right.y / self.cell_size,
+            ),
+        }
+    }
+
+    /// Indices of the claims that intersect the bounds in input order.
+    fn query_indices(&self, bounds: &Bounds) -> Vec<usize> {
+        let range = self.cells(bounds);
+        let mut found: Vec<usize> = self.wide.clone();
+        if range.len() > self.buckets.len() {
+            let buckets = self
+                .buckets
+                .iter()
+                .filter(|(cell, _)| range.contains(**cell));
+            found.extend(buckets.flat_map(|(_, bucket)| bucket).cloned());
+        } else {
+            let buckets = range.cells().filter_map(|cell| self.buckets.get(&cell));
+            found.extend(buckets.flatten().cloned());
+        }
+        found.sort();
+        found.dedup();
+        found.retain(|e| {
+            self.claims[*e]
+                .bounds()
+                .is_some_and(|b| b.intersection(bounds).is_some())
+        });
+        found
+    }
+
+    /// Claims that cover any part of the rectangle.
+    pub fn query(&self, bounds: &Bounds) -> Vec<&'a Claim> {
+        let claims = self.claims;
+        self.query_indices(bounds)
+            .into_iter()
+            .map(|e| &claims[e])
+            .collect()
+    }
+
+    /// Claims that cover the square inch at the coordinate.
+    pub fn at(&self, coord: Coord) -> Vec<&'a Claim> {
+        self.query(&Bounds::new(coord.x, coord.y, coord.x, coord.y))
+    }
+
+    /// Claims that overlap the given one, leaving out the given claim itself when it is one of
+    /// the indexed claims. Other claims that happen to share its id are still included.
+    pub fn overlapping(&self, claim: &Claim) -> Vec<&'a Claim> {
+        match claim.bounds() {
+            Some(bounds) => self
+                .query(&bounds)
+                .into_iter()
+                .filter(|c| !ptr::eq(*c, claim))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Every pair of overlapping claims, each pair listed once in input order.
+    pub fn overlapping_pairs(&self) -> Vec<Overlap<'a>> {
+        let claims = self.claims;
+        let mut pairs = Vec::new();
+        for (i, a) in claims.iter().enumerate() {
+            let bounds = match a.bounds() {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            for j in self.query_indices(&bounds).into_iter().filter(|j| *j > i) {
+                let b = &claims[j];
+                if let Some(shared) = a.intersection(b) {
+                    pairs.push(Overlap { a, b, shared });
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// Every claim that does not overlap any other claim, in input order. Claims with no area are
+/// left out since they do not claim any fabric to keep intact.
+pub fn intact_claims(claims: &[Claim]) -> Vec<&Claim> {
+    let index = ClaimIndex::new(claims);
+    claims
+        .iter()
+        .filter(|claim| claim.bounds().is_some() && index.overlapping(claim).is_empty())
+        .collect()
+}
+
+/// Outcome of looking for the single claim that does not overlap any other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Intact<'a> {
+    None,
+    One(&'a Claim),
+    /// More than one claim is intact, in input order.
+    Several(Vec<&'a Claim>),
+}
+
+impl<'a> Intact<'a> {
+    pub fn find(claims: &'a [Claim]) -> Self {
+        let mut intact = intact_claims(claims);
+        match intact.len() {
+            0 => Intact::None,
+            1 => Intact::One(intact.remove(0)),
+            _ => Intact::Several(intact),
+        }
+    }
+}
+
+/// Smallest rectangle containing every non-empty claim.
+fn fabric_bounds(claims: &[Claim]) -> Option<Bounds> {
+    let corners: Vec<Coord> = claims
+        .iter()
+        .filter_map(|c| c.bounds())
+        .flat_map(|b| vec![b.top_left, b.bottom_right])
+        .collect();
+    if corners.is_empty() {
+        None
+    } else {
+        Some(Bounds::from_coords(&corners))
+    }
+}
+
+/// Offsets of each row of `inner` within a row by row grid covering `outer`.
+fn grid_rows(outer: &Bounds, inner: &Bounds) -> impl Iterator<Item = Range<usize>> {
+    let top_left = outer.normalize(&inner.top_left);
+    let stride = outer.width();
+    let width = inner.width();
+    (0..inner.height()).map(move |i| {
+        let start = (top_left.y + i) * stride + top_left.x;
+        start..start + width
+    })
+}
+
+/// Number of claims covering each square inch within the bounds, stored row by row.
+pub fn claim_counts(claims: &[Claim], bounds: &Bounds) -> Vec<usize> {
+    let mut grid = vec![0usize; bounds.area()];
+    for shared in claims
+        .iter()
+        .filter_map(|c| c.bounds()?.intersection(bounds))
+    {
+        for row in grid_rows(bounds, &shared) {
+            for count in &mut grid[row] {
+                *count += 1;
+            }
+        }
+    }
+    grid
+}
+
+/// Colour for a square inch claimed `count` times.
+fn heat_colour(count: usize) -> [u8; 3] {
+    match count {
+        0 => [0, 0, 0],
+        1 => [40, 70, 160],
+        2 => [240, 200, 40],
+        3 => [240, 120, 30],
+        _ => [220, 30, 30],
+    }
+}
+
+/// Colour used to highlight intact claims.
+const INTACT_COLOUR: [u8; 3] = [40, 200, 80];
+
+/// Write a binary PPM image of the fabric where each pixel is one square inch coloured by how
+/// many claims cover it. Unclaimed fabric is black, singly claimed fabric blue and contested
+/// fabric runs from yellow to red as the number of claims grows. Intact claims are drawn in
+/// green. The image covers the smallest rectangle containing every claim.
+pub fn write_heatmap<W: Write>(claims: &[Claim], mut out: W) -> Result<()> {
+    let bounds = fabric_bounds(claims).ok_or_else(|| Error::new(ErrorKind::EmptyInput, ""))?;
+
+    write!(out, "P6\n{} {}\n255\n", bounds.width(), bounds.height())?;
+    let mut pixels: Vec<u8> = claim_counts(claims, &bounds)
+        .into_iter()
+        .flat_map(|count| heat_colour(count).to_vec())
+        .collect();
+    // Intact claims never overlap so each pixel is painted over at most once.
+    for intact in intact_claims(claims).iter().filter_map(|c| c.bounds()) {
+        for row in grid_rows(&bounds, &intact) {
+            for pixel in pixels[row.start * 3..row.end * 3].chunks_mut(3) {
+                pixel.copy_from_slice(&INTACT_COLOUR);
+            }
+        }
+    }
+    out.write_all(&pixels)?;
+    Ok(())
+}
+
+/// How contested a single claim is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contention {
+    pub id: usize,
+    pub area: usize,
+    /// Square inches of the claim that at least one other claim also covers.
+    pub contested_area: usize,
+    /// Ids of the other claims that overlap this one in input order.
+    pub colliding: Vec<usize>,
+}
+
+/// Compute the contention of every claim in input order.
+pub fn contention(claims: &[Claim]) -> Vec<Contention> {
+    let index = ClaimIndex::new(claims);
+    claims
+        .iter()
+        .map(|claim| {
+            let others = index.overlapping(claim);
+
+            let mut contested_area = 0;
+            if let Some(bounds) = claim.bounds() {
+                let mut contested = vec![false; bounds.area()];
+                for shared in others.iter().filter_map(|c| claim.intersection(c)) {
+                    for row in grid_rows(&bounds, &shared) {
+                        for cell in &mut contested[row] {
+                            *cell = true;
+                        }
+                    }
+                }
+                contested_area = contested.iter().filter(|c| **c).count();
+            }
+
+            let mut colliding: Vec<usize> = Vec::new();
+            for other in others {
+                if !colliding.contains(&other.id) {
+                    colliding.push(other.id);
+                }
+            }
+
+            Contention {
+                id: claim.id,
+                area: claim.width * claim.height,
+                contested_area,
+                colliding,
+            }
+        })
+        .collect()
+}
+
+/// Write the contention report as CSV with a header row. Colliding claim ids are separated by
+/// spaces within their column.
+pub fn write_contention_csv<W: Write>(report: &[Contention], mut out: W) -> Result<()> {
+    writeln!(out, "id,area,contested_area,collisions,colliding_ids")?;
+    for c in report {
+        let ids: Vec<String> = c.colliding.iter().map(|id| id.to_string()).collect();
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            c.id,
+            c.area,
+            c.contested_area,
+            c.colliding.len(),
+            ids.join(" ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Write the claims one per line in the puzzle input format.
+pub fn write_claims<W: Write>(claims: &[Claim], mut out: W) -> Result<()> {
+    for claim in claims {
+        writeln!(out, "{}", claim)?;
+    }
+    Ok(())
+}
+
+/// How generated claims are arranged on the fabric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// No two claims overlap.
+    Disjoint,
+    /// Every claim overlaps at least one other claim.
+    Overlapping,
+}
+
+/// Xorshift generator so that fixtures can be reproduced from their seed.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // Xorshift gets stuck at zero so mix the seed into a non-zero state.
+        XorShift(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniformly pick a value in `low..=high`.
+    fn between(&mut self, low: usize, high: usize) -> usize {
+        low + (self.next() % (high - low + 1) as u64) as usize
+    }
+}
+
+/// Generate `count` claims numbered from 1 with sides of 1 to `max_side` inches.
+///
+/// Disjoint claims are each placed somewhere within their own slot of a square grid of slots.
+/// Overlapping claims are each placed so that they cover part of an earlier claim, and the first
+/// claim is covered by the second. The same seed always produces the same claims.
+pub fn generate_claims(count: usize, max_side: usize, layout: Layout, seed: u64) -> Vec<Claim> {
+    let max_side = max_side.max(1);
+    let mut rng = XorShift::new(seed);
+    let mut claims: Vec<Claim> = Vec::with_capacity(count);
+    let columns = (1..).find(|c| c * c >= count).unwrap_or(1);
+    for e in 0..count {
+        let width = rng.between(1, max_side);
+        let height = rng.between(1, max_side);
+        let (x, y) = match layout {
+            Layout::Disjoint => {
+                let slot = max_side + 1;
+                (
+                    (e % columns) * slot + rng.between(0, max_side - width),
+                    (e / columns) * slot + rng.between(0, max_side - height),
+                )
+            }
+            Layout::Overlapping if e == 0 => (max_side, max_side),
+            Layout::Overlapping => {
+                // Cover a random inch of an earlier claim with some inch of this claim.
+                let other = &claims[if e == 1 { 0 } else { rng.between(0, e - 1) }];
+                let target_x = rng.between(other.x, other.x + other.width - 1);
+                let target_y = rng.between(other.y, other.y + other.height - 1);
+                (
+                    target_x - rng.between(0, width - 1).min(target_x),
+                    target_y - rng.between(0, height - 1).min(target_y),
+                )
+            }
+        };
+        claims.push(Claim {
+            id: e + 1,
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+    claims
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    Ok(overlap_area(&parse(input)?))
+}
+
+/// Id of the only intact claim, or an error if several claims are intact.
+pub fn part2(input: &str) -> Result<Option<usize>> {
+    match Intact::find(&parse(input)?) {
+        Intact::None => Ok(None),
+        Intact::One(claim) => Ok(Some(claim.id)),
+        Intact::Several(claims) => {
+            let ids: Vec<String> = claims.iter().map(|c| format!("#{}", c.id)).collect();
+            Err(Error::new(
+                ErrorKind::Ambiguous(claims.len()),
+                &ids.join(" "),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::input::Source;
+
+    const EXAMPLE: &str = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n";
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(part2(EXAMPLE).unwrap(), Some(3));
+    }
+
+    #[test]
+    fn test_intact() {
+        let claims = parse(EXAMPLE).unwrap();
+        assert_eq!(Intact::find(&claims), Intact::One(&claims[2]));
+        assert_eq!(Intact::find(&claims[..2]), Intact::None);
+        assert_eq!(Intact::find(&[]), Intact::None);
+
+        let claims = parse("#1 @ 0,0: 2x2\n#2 @ 5,5: 1x1\n#3 @ 1,1: 1x1\n#4 @ 9,0: 3x3\n").unwrap();
+        assert_eq!(intact_claims(&claims), vec![&claims[1], &claims[3]]);
+        assert_eq!(
+            Intact::find(&claims),
+            Intact::Several(vec![&claims[1], &claims[3]])
+        );
+        let err = part2("#1 @ 0,0: 1x1\n#2 @ 2,2: 1x1\n").unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Ambiguous(2)));
+        assert_eq!(err.text, "#1 #2");
+        assert_eq!(part2("#1 @ 0,0: 1x1\n#2 @ 0,0: 1x1\n").unwrap(), None);
+
+        // Claims with no area are never intact.
+        assert_eq!(part2("#1 @ 0,0: 2x2\n#2 @ 5,5: 0x3\n").unwrap(), Some(1));
+        assert_eq!(part2("#1 @ 0,0: 0x2\n").unwrap(), None);
+    }
+
+    #[test]
+    fn test_overlap_area() {
+        let claims = parse("#1 @ 0,0: 4x4\n#2 @ 2,2: 4x4\n#3 @ 3,3: 1x1\n#4 @ 6,0: 2x2\n").unwrap();
+        // Area claimed three times still only counts once.
+        assert_eq!(overlap_area(&claims), 4);
+        assert_eq!(overlap_area(&[]), 0);
+    }
+
+    #[test]
+    fn test_overlap_area_large_coordinates() {
+        let input = "#1 @ 1000000,2000000: 10x10\n#2 @ 1000005,2000005: 10x10\n#3 @ 0,0: 1x1\n";
+        assert_eq!(part1(input).unwrap(), 25);
+    }
+
+    #[test]
+    fn test_parse_overflowing_claim() {
+        let max = usize::MAX;
+        let input = format!("#1 @ 0,0: 1x1\n#2 @ {},0: 10x10\n", max - 5);
+        let e = parse(&input).unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Overflow));
+        assert_eq!(e.line, Some(2));
+        assert_eq!(e.text, format!("#2 @ {},0: 10x10", max - 5));
+        assert!(format!("#1 @ 0,{}: 1x1", max).parse::<Claim>().is_err());
+
+        // Claims reaching right up to the largest coordinate are fine.
+        let claim: Claim = format!("#1 @ {},0: 5x1", max - 5).parse().unwrap();
+        assert_eq!(claim.bounds().unwrap().bottom_right.x, max - 1);
+    }
+
+    #[test]
+    fn test_index_queries() {
+        let claims = parse(EXAMPLE).unwrap();
+        let index = ClaimIndex::new(&claims);
+        let ids = |found: Vec<&Claim>| found.iter().map(|c| c.id).collect::<Vec<usize>>();
+
+        assert_eq!(ids(index.at(Coord::new(3, 3))), vec![1, 2]);
+        assert_eq!(ids(index.at(Coord::new(5, 5))), vec![3]);
+        assert_eq!(ids(index.at(Coord::new(0, 0))), vec![]);
+        assert_eq!(ids(index.query(&Bounds::new(0, 0, 5, 5))), vec![1, 2, 3]);
+        assert_eq!(ids(index.overlapping(&claims[0])), vec![2]);
+        assert_eq!(ids(index.overlapping(&claims[2])), vec![]);
+    }
+
+    #[test]
+    fn test_index_duplicate_ids() {
+        let claims = parse("#1 @ 0,0: 2x2\n#1 @ 1,1: 2x2\n#2 @ 9,9: 1x1\n").unwrap();
+        let index = ClaimIndex::new(&claims);
+        assert_eq!(index.overlapping(&claims[0]), vec![&claims[1]]);
+        assert_eq!(index.overlapping(&claims[1]), vec![&claims[0]]);
+        assert_eq!(Intact::find(&claims), Intact::One(&claims[2]));
+        let report = contention(&claims);
+        assert_eq!(
+            (report[0].contested_area, &report[0].colliding),
+            (1, &vec![1])
+        );
+    }
+
+    #[test]
+    fn test_index_overlapping_pairs() {
+        let claims = parse("#1 @ 0,0: 4x4\n#2 @ 2,2: 4x4\n#3 @ 3,3: 1x1\n#4 @ 6,0: 2x2\n").unwrap();
+        let index = ClaimIndex::new(&claims);
+        let pairs: Vec<(usize, usize, usize)> = index
+            .overlapping_pairs()
+            .iter()
+            .map(|o| (o.a.id, o.b.id, o.shared.area()))
+            .collect();
+        assert_eq!(pairs, vec![(1, 2, 4), (1, 3, 1), (2, 3, 1)]);
+    }
+
+    #[test]
+    fn test_index_wide_claims_and_queries() {
+        // One huge claim among many tiny ones is kept aside rather than bucketed.
+        let max = usize::MAX;
+        let mut input: String = (1..=200)
+            .map(|id| format!("#{} @ {},0: 1x1\n", id, id * 3))
+            .collect();
+        input.push_str("#201 @ 0,0: 1000000x1000000\n");
+        input.push_str(&format!("#202 @ {},{}: 1x1\n", max - 1, max - 1));
+        let claims = parse(&input).unwrap();
+        let index = ClaimIndex::new(&claims);
+        assert_eq!(index.wide, vec![200]);
+        assert_eq!(index.buckets.values().map(Vec::len).sum::<usize>(), 201);
+
+        // Queries spanning the whole coordinate space scan the buckets instead of the grid.
+        let ids = |found: Vec<&Claim>| found.iter().map(|c| c.id).collect::<Vec<usize>>();
+        assert_eq!(index.query(&Bounds::new(0, 0, max, max)).len(), 202);
+        assert_eq!(
+            ids(index.query(&Bounds::new(4, 0, max - 2, max - 2))),
+            (2..=201).collect::<Vec<usize>>()
+        );
+        assert_eq!(ids(index.at(Coord::new(6, 0))), vec![2, 201]);
+        assert_eq!(ids(index.at(Coord::new(max - 1, max - 1))), vec![202]);
+        assert_eq!(index.overlapping(&claims[200]).len(), 200);
+        assert_eq!(index.overlapping_pairs().len(), 200);
+    }
+
+    #[test]
+    fn test_index_matches_brute_force() {
+        let input = Source::from_arg(None, 3).read().unwrap();
+        let claims = parse(&input).unwrap();
+        let index = ClaimIndex::new(&claims);
+        let mut expected = 0;
+        for i in 0..claims.len() {
+            for j in (i + 1)..claims.len() {
+                if claims[i].overlaps(&claims[j]) {
+                    expected += 1;
+                }
+            }
+        }
+        assert_eq!(index.overlapping_pairs().len(), expected);
+    }
+
+    #[test]
+    fn test_claim_counts() {
+        let claims = parse(EXAMPLE).unwrap();
+        let counts = claim_counts(&claims, &Bounds::new(2, 2, 4, 4));
+        assert_eq!(counts, vec![0, 1, 1, 1, 2, 2, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_write_heatmap() {
+        let claims = parse(EXAMPLE).unwrap();
+        let mut out = Vec::new();
+        write_heatmap(&claims, &mut out).unwrap();
+
+        let header = b"P6\n6 6\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 6 * 6 * 3);
+        let pixel = |x: usize, y: usize| {
+            let i = ((y - 1) * 6 + (x - 1)) * 3;
+            [pixels[i], pixels[i + 1], pixels[i + 2]]
+        };
+        assert_eq!(pixel(1, 1), heat_colour(0));
+        assert_eq!(pixel(1, 3), heat_colour(1));
+        assert_eq!(pixel(3, 3), heat_colour(2));
+        assert_eq!(pixel(5, 5), INTACT_COLOUR);
+        assert_eq!(pixel(6, 6), INTACT_COLOUR);
+    }
+
+    #[test]
+    fn test_contention() {
+        let claims = parse("#1 @ 0,0: 4x4\n#2 @ 2,2: 4x4\n#3 @ 3,3: 1x1\n#4 @ 6,0: 2x2\n").unwrap();
+        let report = contention(&claims);
+        assert_eq!(
+            report[0],
+            Contention {
+                id: 1,
+                area: 16,
+                contested_area: 4,
+                colliding: vec![2, 3],
+            }
+        );
+        assert_eq!(report[1].contested_area, 4);
+        assert_eq!(report[1].colliding, vec![1, 3]);
+        assert_eq!(report[2].contested_area, 1);
+        assert_eq!(report[2].colliding, vec![1, 2]);
+        assert_eq!(report[3].contested_area, 0);
+        assert!(report[3].colliding.is_empty());
+
+        let mut out = Vec::new();
+        write_contention_csv(&report, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "id,area,contested_area,collisions,colliding_ids\n\
+             1,16,4,2,2 3\n\
+             2,16,4,2,1 3\n\
+             3,1,1,2,1 2\n\
+             4,4,0,0,\n"
+        );
+    }
+
+    #[test]
+    fn test_contention_matches_overlap_area() {
+        // Summing contested area counts fabric once per claim covering it.
+        let input = Source::from_arg(None, 3).read().unwrap();
+        let claims = parse(&input).unwrap();
+        let contested: usize = contention(&claims).iter().map(|c| c.contested_area).sum();
+        let counts = claim_counts(&claims, &fabric_bounds(&claims).unwrap());
+        let expected: usize = counts.iter().filter(|c| **c > 1).sum();
+        assert_eq!(contested, expected);
+    }
+
+    #[test]
+    fn test_display() {
+        let line = "#123 @ 3,2: 5x4";
+        assert_eq!(line.parse::<Claim>().unwrap().to_string(), line);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..50 {
+            for layout in &[Layout::Disjoint, Layout::Overlapping] {
+                let claims = generate_claims(40, 12, *layout, seed);
+                let mut out = Vec::new();
+                write_claims(&claims, &mut out).unwrap();
+                let input = String::from_utf8(out).unwrap();
+                assert_eq!(parse(&input).unwrap(), claims, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_layouts() {
+        for seed in 0..50 {
+            let claims = generate_claims(30, 8, Layout::Disjoint, seed);
+            assert_eq!(claims.len(), 30);
+            assert!(ClaimIndex::new(&claims).overlapping_pairs().is_empty());
+            assert_eq!(overlap_area(&claims), 0);
+
+            let claims = generate_claims(30, 8, Layout::Overlapping, seed);
+            let index = ClaimIndex::new(&claims);
+            assert!(claims.iter().all(|c| !index.overlapping(c).is_empty()));
+            assert_eq!(Intact::find(&claims), Intact::None);
+        }
+        assert_eq!(
+            generate_claims(10, 5, Layout::Overlapping, 7),
+            generate_claims(10, 5, Layout::Overlapping, 7)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let claim = "#123 @ 3,2: 5x4".parse::<Claim>().unwrap();
+        assert_eq!(
+            claim,
+            Claim {
+                id: 123,
+                x: 3,
+                y: 2,
+                width: 5,
+                height: 4,
+            }
+        );
+        assert!("#123 @ 3,2 5x4".parse::<Claim>().is_err());
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Source::from_arg(None, 3).read().unwrap();
+        assert_eq!(part1(&input).unwrap(), 116140);
+        assert_eq!(part2(&input).unwrap(), Some(574));
+    }
+
+    #[test]
+    fn test_overlap() {
+        let c1 = Claim {
+            id: 1,
+            x: 3,
+            y: 4,
+            width: 5,
+            height: 6,
+        };
+        let c2 = Claim {
+            id: 2,
+            x: 10,
+            y: 4,
+            width: 5,
+            height: 6,
+        };
+        assert!(!c1.overlaps(&c2));
+        let c2 = Claim {
+            id: 2,
+            x: 7,
+            y: 4,
+            width: 5,
+            height: 6,
+        };
+        assert!(c1.overlaps(&c2));
+    }
+
+    #[test]
+    fn test_overlap_cross() {
+        // A plus sign where neither claim contains a corner of the other.
+        let c1 = "#1 @ 3,0: 2x8".parse::<Claim>().unwrap();
+        let c2 = "#2 @ 0,3: 8x2".parse::<Claim>().unwrap();
+        assert!(c1.overlaps(&c2));
+        assert!(c2.overlaps(&c1));
+        assert_eq!(c1.intersection(&c2), Some(Bounds::new(3, 3, 4, 4)));
+        assert_eq!(c2.intersection(&c1), Some(Bounds::new(3, 3, 4, 4)));
+    }
+
+    #[test]
+    fn test_overlap_edge_touching() {
+        let c1 = "#1 @ 0,0: 2x2".parse::<Claim>().unwrap();
+        let right = "#2 @ 2,0: 2x2".parse::<Claim>().unwrap();
+        let below = "#3 @ 0,2: 2x2".parse::<Claim>().unwrap();
+        let diagonal = "#4 @ 2,2: 2x2".parse::<Claim>().unwrap();
+        for other in &[right, below, diagonal] {
+            assert!(!c1.overlaps(other));
+            assert_eq!(c1.intersection(other), None);
+        }
+
+        // Sharing a single square inch at the corner does overlap.
+        let corner = "#5 @ 1,1: 2x2".parse::<Claim>().unwrap();
+        assert_eq!(c1.intersection(&corner), Some(Bounds::new(1, 1, 1, 1)));
+    }
+
+    #[test]
+    fn test_overlap_containment() {
+        let outer = "#1 @ 0,0: 10x10".parse::<Claim>().unwrap();
+        let inner = "#2 @ 3,4: 2x3".parse::<Claim>().unwrap();
+        assert_eq!(outer.intersection(&inner), Some(Bounds::new(3, 4, 4, 6)));
+        assert_eq!(inner.intersection(&outer), Some(Bounds::new(3, 4, 4, 6)));
+        assert_eq!(outer.intersection(&outer), outer.bounds());
+    }
+
+    #[test]
+    fn test_overlap_empty_claim() {
+        let c1 = "#1 @ 0,0: 10x10".parse::<Claim>().unwrap();
+        let empty = "#2 @ 3,3: 0x5".parse::<Claim>().unwrap();
+        assert!(!c1.overlaps(&empty));
+        assert!(!empty.overlaps(&c1));
+    }
+
+    #[test]
+    fn test_overlap2() {
+        let c1 = Claim {
+            id: 1395,
+            x: 478,
+            y: 475,
+            width: 9,
+            height: 9,
+        };
+        let c2 = Claim {
+            id: 188,
+            x: 907,
+            y: 475,
+            width: 29,
+            height: 14,
+        };
+        assert!(!c1.overlaps(&c2));
+        assert!(!c2.overlaps(&c1));
+    }
+}
@@ -0,0 +1,1132 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-05
+
+//! Day 4: Repose Record.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+
+use ansi_term::Colour::Red;
+use chrono::prelude::*;
+use chrono::Duration;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::{parse_int, parse_lines, Error, ErrorKind, Result};
+
+lazy_static! {
+    // Line is: [1518-11-01 00:00] Guard #10 begins shift
+    // Whitespace may vary and the time may include seconds.
+    static ref RE: Regex =
+        Regex::new(r"^\s*\[\s*(\d+)-(\d+)-(\d+)\s+(\d+):(\d+)(?::(\d+))?\s*\]\s*(.*?)\s*$").unwrap();
+
+    static ref PHRASES: Phrases = Phrases::default();
+}
+
+#[derive(Debug, PartialOrd, PartialEq, Eq, Ord)]
+pub enum Behavior {
+    BeginsShift(usize),
+    FallsAsleep,
+    WakesUp,
+}
+
+#[derive(Debug, PartialOrd, PartialEq, Eq, Ord)]
+pub struct Event {
+    pub datetime: DateTime<Utc>,
+    pub behavior: Behavior,
+}
+
+/// Phrases the log uses for each behavior as regular expressions. Phrases must match the whole
+/// event text, ignoring case, after runs of whitespace are reduced to a single space.
+#[derive(Debug, Clone)]
+pub struct Phrases {
+    begins_shift: Vec<Regex>,
+    falls_asleep: Vec<Regex>,
+    wakes_up: Vec<Regex>,
+}
+
+impl Default for Phrases {
+    /// Phrases from the puzzle.
+    fn default() -> Self {
+        Phrases::empty()
+            .begins_shift(r"guard #(\d+) begins shift")
+            .and_then(|p| p.falls_asleep("falls asleep"))
+            .and_then(|p| p.wakes_up("wakes up"))
+            .unwrap()
+    }
+}
+
+fn phrase(pattern: &str) -> Result<Regex> {
+    Regex::new(&format!("(?i)^(?:{})$", pattern))
+        .map_err(|e| Error::new(ErrorKind::Pattern(e), pattern))
+}
+
+impl Phrases {
+    /// No phrases at all, to be added to with the builder methods.
+    pub fn empty() -> Self {
+        Phrases {
+            begins_shift: Vec::new(),
+            falls_asleep: Vec::new(),
+            wakes_up: Vec::new(),
+        }
+    }
+
+    /// Add a phrase for a guard beginning their shift. The first group captures the guard id.
+    pub fn begins_shift(mut self, pattern: &str) -> Result<Self> {
+        let re = phrase(pattern)?;
+        if re.captures_len() < 2 {
+            let expected = "pattern with a group capturing the guard id";
+            return Err(Error::new(ErrorKind::Syntax(expected), pattern));
+        }
+        self.begins_shift.push(re);
+        Ok(self)
+    }
+
+    /// Add a phrase for a guard falling asleep.
+    pub fn falls_asleep(mut self, pattern: &str) -> Result<Self> {
+        self.falls_asleep.push(phrase(pattern)?);
+        Ok(self)
+    }
+
+    /// Add a phrase for a guard waking up.
+    pub fn wakes_up(mut self, pattern: &str) -> Result<Self> {
+        self.wakes_up.push(phrase(pattern)?);
+        Ok(self)
+    }
+
+    /// Parse a single log line.
+    pub fn parse_event(&self, line: &str) -> Result<Event> {
+        let caps = RE
+            .captures(line)
+            .ok_or_else(|| Error::new(ErrorKind::Syntax("[YYYY-MM-DD hh:mm[:ss]] event"), line))?;
+        let year = parse_int::<i32>(&caps[1], line)?;
+        let month = parse_int::<u32>(&caps[2], line)?;
+        let day = parse_int::<u32>(&caps[3], line)?;
+        let hour = parse_int::<u32>(&caps[4], line)?;
+        let minute = parse_int::<u32>(&caps[5], line)?;
+        let second = match caps.get(6) {
+            Some(second) => parse_int::<u32>(second.as_str(), line)?,
+            None => 0,
+        };
+
+        let datetime = NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, second))
+            .map(|naive| DateTime::<Utc>::from_utc(naive, Utc))
+            .ok_or_else(|| Error::new(ErrorKind::InvalidDate, line))?;
+
+        let event = caps[7].split_whitespace().collect::<Vec<_>>().join(" ");
+        let matches = |phrases: &[Regex]| phrases.iter().any(|re| re.is_match(&event));
+        let behavior =
+            if let Some(caps) = self.begins_shift.iter().find_map(|re| re.captures(&event)) {
+                let id = caps.get(1).map_or("", |id| id.as_str());
+                Behavior::BeginsShift(parse_int(id, line)?)
+            } else if matches(&self.falls_asleep) {
+                Behavior::FallsAsleep
+            } else if matches(&self.wakes_up) {
+                Behavior::WakesUp
+            } else {
+                return Err(Error::new(ErrorKind::UnknownEvent, line));
+            };
+        Ok(Event { datetime, behavior })
+    }
+}
+
+impl FromStr for Event {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        PHRASES.parse_event(line)
+    }
+}
+
+/// Event along with the log line it was read from.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry<'a> {
+    /// Line number starting from 1.
+    pub line: usize,
+    pub text: &'a str,
+    pub event: Event,
+}
+
+impl<'a> Entry<'a> {
+    fn error(&self, problem: &'static str) -> Error {
+        Error::new(ErrorKind::Sequence(problem), self.text).at_line(self.line)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sleep {
+    pub begin: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// First whole minute at or after the time.
+fn ceil_minute(t: DateTime<Utc>) -> DateTime<Utc> {
+    let floor = t.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap();
+    if floor == t {
+        t
+    } else {
+        floor + Duration::minutes(1)
+    }
+}
+
+impl Sleep {
+    /// First minute the guard is asleep for. A minute counts as asleep when the guard is asleep
+    /// as it starts, so sleep beginning partway through a minute counts from the next one.
+    pub fn first_minute(&self) -> DateTime<Utc> {
+        ceil_minute(self.begin)
+    }
+
+    /// Number of minutes the guard is asleep for, counted the same way as `first_minute`.
+    pub fn minutes(&self) -> usize {
+        (ceil_minute(self.end) - self.first_minute())
+            .num_minutes()
+            .max(0) as usize
+    }
+
+    /// Whether the guard is asleep for the minute starting at the time.
+    fn covers(&self, minute: DateTime<Utc>) -> bool {
+        let first = self.first_minute();
+        first <= minute && minute < first + Duration::minutes(self.minutes() as i64)
+    }
+}
+
+/// One guard's time on duty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shift {
+    pub guard: usize,
+    pub begin: DateTime<Utc>,
+    pub sleeps: Vec<Sleep>,
+}
+
+impl Shift {
+    /// Date of the midnight hour the shift covers. Guards may begin their shift shortly before
+    /// midnight on the previous day.
+    pub fn date(&self) -> NaiveDate {
+        let date = self.begin.naive_utc().date();
+        if self.begin.hour() == 0 {
+            date
+        } else {
+            date.succ()
+        }
+    }
+
+    /// Whether the guard is asleep during each minute of the midnight hour.
+    pub fn asleep_minutes(&self) -> [bool; 60] {
+        let midnight = DateTime::<Utc>::from_utc(self.date().and_hms(0, 0, 0), Utc);
+        let mut minutes = [false; 60];
+        for (e, asleep) in minutes.iter_mut().enumerate() {
+            let t = midnight + Duration::minutes(e as i64);
+            *asleep = self.sleeps.iter().any(|s| s.covers(t));
+        }
+        minutes
+    }
+}
+
+/// Write a chart of the midnight hour with one row per shift in the format of the puzzle
+/// statement, where `#` marks a minute the guard is asleep. Asleep minutes are drawn in red
+/// when `colour` is set.
+pub fn write_sleep_chart<W: Write>(shifts: &[Shift], colour: bool, mut out: W) -> Result<()> {
+    let id_width = shifts
+        .iter()
+        .map(|s| s.guard.to_string().len() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(2);
+    let tens: String = (0..60).map(|m| (b'0' + m / 10) as char).collect();
+    let ones: String = (0..60).map(|m| (b'0' + m % 10) as char).collect();
+    writeln!(out, "Date   {:<w$}  Minute", "ID", w = id_width)?;
+    writeln!(out, "       {:<w$}  {}", "", tens, w = id_width)?;
+    writeln!(out, "       {:<w$}  {}", "", ones, w = id_width)?;
+    for shift in shifts {
+        let strip: String = shift
+            .asleep_minutes()
+            .iter()
+            .map(|&asleep| match (asleep, colour) {
+                (true, true) => Red.paint("#").to_string(),
+                (true, false) => "#".to_string(),
+                (false, _) => ".".to_string(),
+            })
+            .collect();
+        writeln!(
+            out,
+            "{}  {:<w$}  {}",
+            shift.date().format("%m-%d"),
+            format!("#{}", shift.guard),
+            strip,
+            w = id_width
+        )?;
+    }
+    Ok(())
+}
+
+/// Parse the guard log and return its entries in chronological order.
+pub fn parse_log(input: &str) -> Result<Vec<Entry<'_>>> {
+    parse_log_with(input, &PHRASES)
+}
+
+/// Parse a guard log written with other phrases and return its entries in chronological order.
+pub fn parse_log_with<'a>(input: &'a str, phrases: &Phrases) -> Result<Vec<Entry<'a>>> {
+    let events = parse_lines(input, |line| phrases.parse_event(line))?;
+    if events.is_empty() {
+        return Err(Error::new(ErrorKind::EmptyInput, ""));
+    }
+    let mut entries: Vec<Entry> = input
+        .lines()
+        .zip(events)
+        .enumerate()
+        .map(|(i, (text, event))| Entry {
+            line: i + 1,
+            text,
+            event,
+        })
+        .collect();
+    // Events at the same time stay in log order.
+    entries.sort_by_key(|e| e.event.datetime);
+    Ok(entries)
+}
+
+/// Parse the guard log and return its events in chronological order.
+pub fn parse(input: &str) -> Result<Vec<Event>> {
+    Ok(parse_log(input)?.into_iter().map(|e| e.event).collect())
+}
+
+/// Split the chronological log into shifts, checking that every guard falls asleep and wakes up
+/// in turn during their own shift.
+pub fn shifts(entries: &[Entry]) -> Result<Vec<Shift>> {
+    let mut shifts: Vec<Shift> = Vec::new();
+    // Entry of the guard falling asleep while they are asleep.
+    let mut asleep: Option<&Entry> = None;
+    for entry in entries {
+        match (&entry.event.behavior, asleep) {
+            (Behavior::BeginsShift(_), Some(fell)) => {
+                return Err(fell.error("guard never wakes up before the next shift"));
+            }
+            (Behavior::BeginsShift(id), None) => shifts.push(Shift {
+                guard: *id,
+                begin: entry.event.datetime,
+                sleeps: Vec::new(),
+            }),
+            (Behavior::FallsAsleep, _) if shifts.is_empty() => {
+                return Err(entry.error("falls asleep before any shift begins"));
+            }
+            (Behavior::FallsAsleep, Some(_)) => {
+                return Err(entry.error("falls asleep while already asleep"));
+            }
+            (Behavior::FallsAsleep, None) => asleep = Some(entry),
+            (Behavior::WakesUp, None) => {
+                return Err(entry.error("wakes up without falling asleep"));
+            }
+            (Behavior::WakesUp, Some(fell)) => {
+                shifts.last_mut().unwrap().sleeps.push(Sleep {
+                    begin: fell.event.datetime,
+                    end: entry.event.datetime,
+                });
+                asleep = None;
+            }
+        }
+    }
+    match asleep {
+        Some(fell) => Err(fell.error("guard never wakes up before the log ends")),
+        None => Ok(shifts),
+    }
+}
+
+/// Gather every sleep of each guard, including guards who never sleep.
+pub fn compute_guard_sleep(shifts: &[Shift]) -> HashMap<usize, Vec<Sleep>> {
+    let mut guards: HashMap<usize, Vec<Sleep>> = HashMap::new();
+    for shift in shifts {
+        guards
+            .entry(shift.guard)
+            .or_default()
+            .extend(shift.sleeps.iter().cloned());
+    }
+    guards
+}
+
+pub const MINUTES_PER_DAY: usize = 24 * 60;
+
+/// Count how many times each minute of the day, starting from midnight, is slept through.
+/// Sleeps may cross midnight and last any number of minutes including none.
+pub fn compute_minute_freq(sleep: &[Sleep]) -> Vec<usize> {
+    // Every sleep adds one to a run of minutes, so record where each run starts and stops and
+    // total them up afterwards. Whole days add to every minute.
+    let mut whole_days = 0;
+    let mut changes = vec![0isize; MINUTES_PER_DAY + 1];
+    for s in sleep {
+        let length = s.minutes();
+        whole_days += length / MINUTES_PER_DAY;
+        let first = s.first_minute();
+        let begin = (first.hour() * 60 + first.minute()) as usize;
+        let end = begin + length % MINUTES_PER_DAY;
+        changes[begin] += 1;
+        if end <= MINUTES_PER_DAY {
+            changes[end] -= 1;
+        } else {
+            // Wrap around past midnight.
+            changes[MINUTES_PER_DAY] -= 1;
+            changes[0] += 1;
+            changes[end - MINUTES_PER_DAY] -= 1;
+        }
+    }
+    let mut count = 0;
+    changes[..MINUTES_PER_DAY]
+        .iter()
+        .map(|change| {
+            count += change;
+            whole_days + count as usize
+        })
+        .collect()
+}
+
+/// Summary of one guard's sleep over the whole log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardStats {
+    pub guard: usize,
+    pub shifts: usize,
+    pub naps: usize,
+    pub total_minutes: usize,
+    /// Mean length of a nap in minutes, zero if the guard never naps.
+    pub mean_nap: f64,
+    pub longest_nap: usize,
+    /// Earliest of the minutes of the day the guard is most often asleep, if they ever sleep.
+    pub modal_minute: Option<usize>,
+    /// Number of naps that cover the modal minute.
+    pub modal_count: usize,
+}
+
+/// Compute the statistics of every guard ordered by guard id.
+pub fn guard_stats(shifts: &[Shift]) -> Vec<GuardStats> {
+    let mut stats: Vec<GuardStats> = compute_guard_sleep(shifts)
+        .iter()
+        .map(|(&guard, sleep)| {
+            let total_minutes: usize = sleep.iter().map(Sleep::minutes).sum();
+            let freq = compute_minute_freq(sleep);
+            let modal_minute = best_minutes(sleep).first().cloned();
+            GuardStats {
+                guard,
+                shifts: shifts.iter().filter(|s| s.guard == guard).count(),
+                naps: sleep.len(),
+                total_minutes,
+                mean_nap: if sleep.is_empty() {
+                    0.0
+                } else {
+                    total_minutes as f64 / sleep.len() as f64
+                },
+                longest_nap: sleep.iter().map(Sleep::minutes).max().unwrap_or(0),
+                modal_minute,
+                modal_count: modal_minute.map_or(0, |m| freq[m]),
+            }
+        })
+        .collect();
+    stats.sort_by_key(|s| s.guard);
+    stats
+}
+
+/// Write the guard statistics as CSV. The modal minute is left empty for guards who never sleep.
+pub fn write_guard_stats_csv<W: Write>(stats: &[GuardStats], mut out: W) -> Result<()> {
+    writeln!(
+        out,
+        "guard,shifts,naps,total_minutes,mean_nap,longest_nap,modal_minute,modal_count"
+    )?;
+    for s in stats {
+        writeln!(
+            out,
+            "{},{},{},{},{:.2},{},{},{}",
+            s.guard,
+            s.shifts,
+            s.naps,
+            s.total_minutes,
+            s.mean_nap,
+            s.longest_nap,
+            s.modal_minute.map_or_else(String::new, |m| m.to_string()),
+            s.modal_count
+        )?;
+    }
+    Ok(())
+}
+
+/// Write the guard statistics as a JSON array with one object per guard. The modal minute is
+/// null for guards who never sleep.
+pub fn write_guard_stats_json<W: Write>(stats: &[GuardStats], mut out: W) -> Result<()> {
+    writeln!(out, "[")?;
+    for (e, s) in stats.iter().enumerate() {
+        let separator = if e + 1 < stats.len() { "," } else { "" };
+        writeln!(
+            out,
+            "  {{\"guard\": {}, \"shifts\": {}, \"naps\": {}, \"total_minutes\": {}, \
+             \"mean_nap\": {:.2}, \"longest_nap\": {}, \"modal_minute\": {}, \
+             \"modal_count\": {}}}{}",
+            s.guard,
+            s.shifts,
+            s.naps,
+            s.total_minutes,
+            s.mean_nap,
+            s.longest_nap,
+            s.modal_minute
+                .map_or_else(|| "null".to_string(), |m| m.to_string()),
+            s.modal_count,
+            separator
+        )?;
+    }
+    writeln!(out, "]")?;
+    Ok(())
+}
+
+/// Guard to sneak past and the minute of the day to do it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Choice {
+    pub guard: usize,
+    pub minute: usize,
+}
+
+impl Choice {
+    /// Puzzle answer for this choice.
+    pub fn answer(&self) -> usize {
+        self.guard * self.minute
+    }
+}
+
+/// Outcome of a strategy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pick {
+    /// No guard ever sleeps.
+    None,
+    One(Choice),
+    /// Several choices score equally well, ordered by guard then minute.
+    Several(Vec<Choice>),
+}
+
+impl Pick {
+    fn from_choices(mut choices: Vec<Choice>) -> Self {
+        choices.sort();
+        match choices.len() {
+            0 => Pick::None,
+            1 => Pick::One(choices[0]),
+            _ => Pick::Several(choices),
+        }
+    }
+}
+
+/// Way of deciding which guard to sneak past and when.
+pub trait Strategy {
+    /// Name the strategy is selected by.
+    fn name(&self) -> &'static str;
+
+    /// Pick from the sleep of each guard along with the shifts it was gathered from.
+    fn choose(&self, guards: &HashMap<usize, Vec<Sleep>>, shifts: &[Shift]) -> Pick;
+}
+
+/// Items that share the greatest key.
+fn best<K: Ord, T>(items: impl IntoIterator<Item = (K, T)>) -> Vec<T> {
+    let mut best: Option<K> = None;
+    let mut found = Vec::new();
+    for (key, item) in items {
+        match best.as_ref().map(|b| key.cmp(b)) {
+            Some(Ordering::Less) => {}
+            Some(Ordering::Equal) => found.push(item),
+            _ => {
+                best = Some(key);
+                found = vec![item];
+            }
+        }
+    }
+    found
+}
+
+/// Minutes the guard is most often asleep on, if they ever sleep.
+fn best_minutes(sleep: &[Sleep]) -> Vec<usize> {
+    let freq = compute_minute_freq(sleep);
+    best(
+        freq.iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(minute, count)| (count, minute)),
+    )
+}
+
+/// Choose the guards scoring best by `key` and then the minute each is most often asleep.
+fn best_guard_then_minute<K, F>(guards: &HashMap<usize, Vec<Sleep>>, key: F) -> Pick
+where
+    K: Ord,
+    F: Fn(usize, &[Sleep]) -> K,
+{
+    let sleepy = guards.iter().filter(|(_, sleep)| !sleep.is_empty());
+    let choices = best(sleepy.map(|(&guard, sleep)| (key(guard, sleep), guard)))
+        .into_iter()
+        .flat_map(|guard| {
+            best_minutes(&guards[&guard])
+                .into_iter()
+                .map(move |minute| Choice { guard, minute })
+        })
+        .collect();
+    Pick::from_choices(choices)
+}
+
+/// Choose the guard and minute with the highest count.
+fn best_guard_minute(counts: &HashMap<usize, Vec<usize>>) -> Pick {
+    let choices = best(counts.iter().flat_map(|(&guard, freq)| {
+        freq.iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(move |(minute, count)| (count, Choice { guard, minute }))
+    }));
+    Pick::from_choices(choices)
+}
+
+/// Strategy 1: the guard with the most minutes asleep and the minute they are most often asleep.
+pub struct MostAsleep;
+
+impl Strategy for MostAsleep {
+    fn name(&self) -> &'static str {
+        "most-asleep"
+    }
+
+    fn choose(&self, guards: &HashMap<usize, Vec<Sleep>>, _: &[Shift]) -> Pick {
+        best_guard_then_minute(guards, |_, sleep| {
+            sleep.iter().map(Sleep::minutes).sum::<usize>()
+        })
+    }
+}
+
+/// Strategy 2: the guard most frequently asleep on the same minute.
+pub struct MostFrequentMinute;
+
+impl Strategy for MostFrequentMinute {
+    fn name(&self) -> &'static str {
+        "most-frequent-minute"
+    }
+
+    fn choose(&self, guards: &HashMap<usize, Vec<Sleep>>, _: &[Shift]) -> Pick {
+        let counts = guards
+            .iter()
+            .map(|(&guard, sleep)| (guard, compute_minute_freq(sleep)))
+            .collect();
+        best_guard_minute(&counts)
+    }
+}
+
+/// The guard who takes the longest single nap and the minute they are most often asleep.
+pub struct LongestNap;
+
+impl Strategy for LongestNap {
+    fn name(&self) -> &'static str {
+        "longest-nap"
+    }
+
+    fn choose(&self, guards: &HashMap<usize, Vec<Sleep>>, _: &[Shift]) -> Pick {
+        best_guard_then_minute(guards, |_, sleep| sleep.iter().map(Sleep::minutes).max())
+    }
+}
+
+/// Minutes asleep per shift compared exactly.
+#[derive(Debug, Clone, Copy)]
+struct Ratio {
+    minutes: usize,
+    shifts: usize,
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.minutes * other.shifts).cmp(&(other.minutes * self.shifts))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ratio {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ratio {}
+
+/// The guard who sleeps the most minutes per shift worked and the minute they are most often
+/// asleep.
+pub struct SleepRatio;
+
+impl Strategy for SleepRatio {
+    fn name(&self) -> &'static str {
+        "sleep-ratio"
+    }
+
+    fn choose(&self, guards: &HashMap<usize, Vec<Sleep>>, shifts: &[Shift]) -> Pick {
+        best_guard_then_minute(guards, |guard, sleep| Ratio {
+            minutes: sleep.iter().map(Sleep::minutes).sum(),
+            shifts: shifts.iter().filter(|s| s.guard == guard).count().max(1),
+        })
+    }
+}
+
+/// The guard and minute asleep on the most separate shifts, no matter how often the guard
+/// sleeps through that minute within a shift.
+pub struct ConsistentMinute;
+
+impl Strategy for ConsistentMinute {
+    fn name(&self) -> &'static str {
+        "consistent-minute"
+    }
+
+    fn choose(&self, _: &HashMap<usize, Vec<Sleep>>, shifts: &[Shift]) -> Pick {
+        let mut counts: HashMap<usize, Vec<usize>> = HashMap::new();
+        for shift in shifts {
+            let freq = compute_minute_freq(&shift.sleeps);
+            let count = counts
+                .entry(shift.guard)
+                .or_insert_with(|| vec![0; MINUTES_PER_DAY]);
+            for (c, f) in count.iter_mut().zip(freq) {
+                *c += (f > 0) as usize;
+            }
+        }
+        best_guard_minute(&counts)
+    }
+}
+
+/// Every strategy, starting with the two from the puzzle.
+pub fn strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(MostAsleep),
+        Box::new(MostFrequentMinute),
+        Box::new(LongestNap),
+        Box::new(SleepRatio),
+        Box::new(ConsistentMinute),
+    ]
+}
+
+/// Find a strategy by name.
+pub fn strategy(name: &str) -> Option<Box<dyn Strategy>> {
+    strategies().into_iter().find(|s| s.name() == name)
+}
+
+/// Apply the strategy to the guard log.
+pub fn choose(strategy: &dyn Strategy, input: &str) -> Result<Pick> {
+    let shifts = shifts(&parse_log(input)?)?;
+    Ok(strategy.choose(&compute_guard_sleep(&shifts), &shifts))
+}
+
+/// Answer for the strategy, or an error if several choices are equally good.
+pub fn solve(strategy: &dyn Strategy, input: &str) -> Result<Option<usize>> {
+    match choose(strategy, input)? {
+        Pick::None => Ok(None),
+        Pick::One(choice) => Ok(Some(choice.answer())),
+        Pick::Several(choices) => {
+            let text: Vec<String> = choices
+                .iter()
+                .map(|c| format!("#{} minute {}", c.guard, c.minute))
+                .collect();
+            Err(Error::new(
+                ErrorKind::Ambiguous(choices.len()),
+                &text.join(", "),
+            ))
+        }
+    }
+}
+
+/// Strategy 1: the guard with the most minutes asleep multiplied by the minute they are most
+/// often asleep.
+pub fn part1(input: &str) -> Result<Option<usize>> {
+    solve(&MostAsleep, input)
+}
+
+/// Strategy 2: the guard most frequently asleep on the same minute multiplied by that minute.
+pub fn part2(input: &str) -> Result<Option<usize>> {
+    solve(&MostFrequentMinute, input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::input::Source;
+
+    const EXAMPLE: &str = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up
+";
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), Some(240));
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(part2(EXAMPLE).unwrap(), Some(4455));
+    }
+
+    #[test]
+    fn test_parse_unordered() {
+        // Log lines are not guaranteed to be in chronological order.
+        let mut lines: Vec<&str> = EXAMPLE.lines().collect();
+        lines.reverse();
+        assert_eq!(parse(&lines.join("\n")).unwrap(), parse(EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn test_parse_unknown_event() {
+        let input = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] dozes off\n";
+        let e = parse(input).unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::UnknownEvent));
+        assert_eq!(e.line, Some(2));
+    }
+
+    #[test]
+    fn test_shifts() {
+        let shifts = shifts(&parse_log(EXAMPLE).unwrap()).unwrap();
+        assert_eq!(shifts.len(), 5);
+        assert_eq!(shifts[1].guard, 99);
+        assert_eq!(shifts[1].begin, Utc.ymd(1518, 11, 1).and_hms(23, 58, 0));
+        assert_eq!(shifts[0].sleeps.len(), 2);
+        assert_eq!(
+            shifts[0].sleeps[1],
+            Sleep {
+                begin: Utc.ymd(1518, 11, 1).and_hms(0, 30, 0),
+                end: Utc.ymd(1518, 11, 1).and_hms(0, 55, 0),
+            }
+        );
+    }
+
+    #[test]
+    fn test_strategies() {
+        let pick = |name: &str| choose(&*strategy(name).unwrap(), EXAMPLE).unwrap();
+        let one = |guard, minute| Pick::One(Choice { guard, minute });
+        assert_eq!(pick("most-asleep"), one(10, 24));
+        assert_eq!(pick("most-frequent-minute"), one(99, 45));
+        // Guard #10 naps for 25 minutes from both 00:05 and 00:30.
+        assert_eq!(pick("longest-nap"), one(10, 24));
+        // Guard #10 sleeps 50 minutes over 2 shifts and #99 30 minutes over 3.
+        assert_eq!(pick("sleep-ratio"), one(10, 24));
+        assert_eq!(pick("consistent-minute"), one(99, 45));
+        assert!(strategy("unknown").is_none());
+    }
+
+    #[test]
+    fn test_strategy_ties() {
+        let input = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:10] wakes up
+[1518-11-02 00:00] Guard #20 begins shift
+[1518-11-02 00:30] falls asleep
+[1518-11-02 00:35] wakes up
+[1518-11-03 00:00] Guard #30 begins shift
+";
+        let tied = Pick::Several(
+            [(10, 5), (10, 6), (10, 7), (10, 8), (10, 9)]
+                .iter()
+                .chain(&[(20, 30), (20, 31), (20, 32), (20, 33), (20, 34)])
+                .map(|&(guard, minute)| Choice { guard, minute })
+                .collect(),
+        );
+        for strategy in strategies() {
+            assert_eq!(
+                choose(&*strategy, input).unwrap(),
+                tied,
+                "{}",
+                strategy.name()
+            );
+        }
+        let e = part1(input).unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Ambiguous(10)));
+        assert!(e.text.starts_with("#10 minute 5, #10 minute 6"));
+
+        let input = "[1518-11-01 00:00] Guard #10 begins shift\n";
+        assert_eq!(part1(input).unwrap(), None);
+        assert_eq!(part2(input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_guard_stats() {
+        let mut input = EXAMPLE.to_string();
+        input.push_str("[1518-11-06 00:00] Guard #7 begins shift\n");
+        let stats = guard_stats(&shifts(&parse_log(&input).unwrap()).unwrap());
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[1].guard, 10);
+        assert_eq!(
+            stats[2],
+            GuardStats {
+                guard: 99,
+                shifts: 3,
+                naps: 3,
+                total_minutes: 30,
+                mean_nap: 10.0,
+                longest_nap: 10,
+                modal_minute: Some(45),
+                modal_count: 3,
+            }
+        );
+
+        let mut out = Vec::new();
+        write_guard_stats_csv(&stats, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "guard,shifts,naps,total_minutes,mean_nap,longest_nap,modal_minute,modal_count\n\
+             7,1,0,0,0.00,0,,0\n\
+             10,2,3,50,16.67,25,24,2\n\
+             99,3,3,30,10.00,10,45,3\n"
+        );
+
+        let mut out = Vec::new();
+        write_guard_stats_json(&stats, &mut out).unwrap();
+        let json = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[1],
+            "  {\"guard\": 7, \"shifts\": 1, \"naps\": 0, \"total_minutes\": 0, \
+             \"mean_nap\": 0.00, \"longest_nap\": 0, \"modal_minute\": null, \"modal_count\": 0},"
+        );
+        assert!(lines[2].contains("\"mean_nap\": 16.67"));
+        assert!(lines[3].ends_with("\"modal_count\": 3}"));
+        assert_eq!(lines[4], "]");
+    }
+
+    #[test]
+    fn test_minute_freq() {
+        let sleep = |begin: DateTime<Utc>, minutes: i64| Sleep {
+            begin,
+            end: begin + Duration::minutes(minutes),
+        };
+        let day = Utc.ymd(1518, 11, 1);
+        let freq = compute_minute_freq(&[
+            sleep(day.and_hms(0, 5, 0), 20),
+            sleep(day.and_hms(0, 20, 0), 0),
+            sleep(day.and_hms(0, 10, 0), 20),
+        ]);
+        assert_eq!(freq.len(), MINUTES_PER_DAY);
+        assert_eq!((freq[4], freq[5], freq[9], freq[10]), (0, 1, 1, 2));
+        assert_eq!((freq[24], freq[25], freq[29], freq[30]), (2, 1, 1, 0));
+
+        // Crossing midnight and spanning several hours.
+        let freq = compute_minute_freq(&[
+            sleep(day.and_hms(23, 30, 0), 60),
+            sleep(day.and_hms(1, 50, 0), 130),
+        ]);
+        assert_eq!(freq.iter().sum::<usize>(), 190);
+        assert_eq!(
+            (freq[23 * 60 + 29], freq[23 * 60 + 30], freq[1439]),
+            (0, 1, 1)
+        );
+        assert_eq!((freq[0], freq[29], freq[30]), (1, 1, 0));
+        assert_eq!((freq[109], freq[110], freq[239], freq[240]), (0, 1, 1, 0));
+
+        // Longer than a day.
+        let freq = compute_minute_freq(&[sleep(day.and_hms(12, 0, 0), 2 * 1440 + 1)]);
+        assert_eq!((freq[0], freq[719], freq[720], freq[721]), (2, 2, 3, 2));
+    }
+
+    #[test]
+    fn test_minutes_with_seconds() {
+        let input = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05:30] falls asleep
+[1518-11-01 00:07:10] wakes up
+[1518-11-02 00:00] Guard #10 begins shift
+[1518-11-02 00:05:30] falls asleep
+[1518-11-02 00:06:10] wakes up
+[1518-11-03 00:00] Guard #10 begins shift
+[1518-11-03 00:05:30] falls asleep
+[1518-11-03 00:05:50] wakes up
+";
+        let shifts = shifts(&parse_log(input).unwrap()).unwrap();
+        let minutes: Vec<usize> = shifts.iter().map(|s| s.sleeps[0].minutes()).collect();
+        assert_eq!(minutes, vec![2, 1, 0]);
+
+        // The histogram, statistics and chart all agree on which minutes are slept through.
+        let freq = compute_minute_freq(&compute_guard_sleep(&shifts)[&10]);
+        assert_eq!(&freq[5..9], &[0, 2, 1, 0]);
+        let stats = guard_stats(&shifts);
+        assert_eq!((stats[0].total_minutes, stats[0].longest_nap), (3, 2));
+        assert_eq!((stats[0].modal_minute, stats[0].modal_count), (Some(6), 2));
+        assert_eq!(
+            &shifts[0].asleep_minutes()[5..9],
+            &[false, true, true, false]
+        );
+        assert_eq!(&shifts[1].asleep_minutes()[5..8], &[false, true, false]);
+        assert!(!shifts[2].asleep_minutes().iter().any(|m| *m));
+    }
+
+    #[test]
+    fn test_sleep_chart() {
+        let shifts = shifts(&parse_log(EXAMPLE).unwrap()).unwrap();
+        let mut out = Vec::new();
+        write_sleep_chart(&shifts, false, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+Date   ID   Minute
+            000000000011111111112222222222333333333344444444445555555555
+            012345678901234567890123456789012345678901234567890123456789
+11-01  #10  .....####################.....#########################.....
+11-02  #99  ........................................##########..........
+11-03  #10  ........................#####...............................
+11-04  #99  ....................................##########..............
+11-05  #99  .............................................##########.....
+"
+        );
+    }
+
+    #[test]
+    fn test_shifts_same_minute() {
+        // Waking up and falling asleep again within the same minute is fine.
+        let input = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:10] wakes up
+[1518-11-01 00:10] falls asleep
+[1518-11-01 00:20] wakes up
+";
+        let shifts = shifts(&parse_log(input).unwrap()).unwrap();
+        assert_eq!(shifts[0].sleeps.len(), 2);
+        assert_eq!(
+            shifts[0].sleeps[1].begin,
+            Utc.ymd(1518, 11, 1).and_hms(0, 10, 0)
+        );
+    }
+
+    #[test]
+    fn test_shifts_invalid() {
+        let check = |input: &str, problem: &str, line: usize| {
+            let e = shifts(&parse_log(input).unwrap()).unwrap_err();
+            assert!(
+                matches!(e.kind, ErrorKind::Sequence(p) if p == problem),
+                "{}",
+                e
+            );
+            assert_eq!(e.line, Some(line), "{}", problem);
+        };
+        check(
+            "[1518-11-01 00:05] Guard #10 begins shift\n[1518-10-31 23:55] falls asleep\n",
+            "falls asleep before any shift begins",
+            2,
+        );
+        check(
+            "[1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:05] falls asleep\n\
+             [1518-11-01 00:06] falls asleep\n",
+            "falls asleep while already asleep",
+            3,
+        );
+        check(
+            "[1518-11-01 00:00] Guard #10 begins shift\n\
+             [1518-11-01 00:05] falls asleep\n\
+             [1518-11-02 00:00] Guard #10 begins shift\n",
+            "guard never wakes up before the next shift",
+            2,
+        );
+        check(
+            "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep\n",
+            "guard never wakes up before the log ends",
+            2,
+        );
+        check(
+            "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] wakes up\n",
+            "wakes up without falling asleep",
+            2,
+        );
+    }
+
+    #[test]
+    fn test_parse_tolerant() {
+        let expected = parse(EXAMPLE).unwrap();
+        let messy: Vec<String> = EXAMPLE
+            .lines()
+            .map(|line| {
+                line.replace("] ", ":00 ]  ")
+                    .replace("Guard", "GUARD")
+                    .replace("falls asleep", "Falls\tAsleep ")
+            })
+            .collect();
+        assert_eq!(messy[1], "[1518-11-01 00:05:00 ]  Falls\tAsleep ");
+        assert_eq!(parse(&messy.join("\n")).unwrap(), expected);
+
+        let event: Event = "[1518-11-01 00:05:30] wakes up".parse().unwrap();
+        assert_eq!(event.datetime, Utc.ymd(1518, 11, 1).and_hms(0, 5, 30));
+        let e = "[1518-11-01 00:05:60] wakes up"
+            .parse::<Event>()
+            .unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::InvalidDate));
+    }
+
+    #[test]
+    fn test_parse_phrases() {
+        let phrases = Phrases::default()
+            .begins_shift(r"guard (\d+) (?:starts|is on) duty")
+            .and_then(|p| p.falls_asleep("dozes off"))
+            .and_then(|p| p.wakes_up("jolts awake"))
+            .unwrap();
+        let input = "\
+[1518-11-01 00:00] Guard 10 starts duty
+[1518-11-01 00:05] dozes off
+[1518-11-01 00:25] Jolts  awake
+[1518-11-02 00:00] Guard #99 begins shift
+[1518-11-02 00:10] falls asleep
+[1518-11-02 00:15] wakes up
+";
+        let events: Vec<Behavior> = parse_log_with(input, &phrases)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event.behavior)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                Behavior::BeginsShift(10),
+                Behavior::FallsAsleep,
+                Behavior::WakesUp,
+                Behavior::BeginsShift(99),
+                Behavior::FallsAsleep,
+                Behavior::WakesUp,
+            ]
+        );
+        let e = parse_log(input).unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::UnknownEvent));
+        assert_eq!(e.line, Some(1));
+
+        let e = Phrases::empty()
+            .begins_shift("guard begins shift")
+            .unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Syntax(_)));
+        assert_eq!(e.text, "guard begins shift");
+        let e = Phrases::empty().wakes_up("wakes (up").unwrap_err();
+        assert!(matches!(e.kind, ErrorKind::Pattern(_)));
+        assert_eq!(e.text, "wakes (up");
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Source::from_arg(None, 4).read().unwrap();
+        assert_eq!(part1(&input).unwrap(), Some(39584));
+        assert_eq!(part2(&input).unwrap(), Some(55053));
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-06
+
+//! Day 5: Alchemical Reduction.
+
+/// Parse the polymer, ignoring anything that isn't a unit.
+pub fn parse(input: &str) -> Vec<char> {
+    input.chars().filter(|c| c.is_ascii_alphabetic()).collect()
+}
+
+/// Fully react the polymer and return the number of units that remain.
+pub fn react<I: IntoIterator<Item = char>>(polymer: I) -> usize {
+    let mut v: Vec<char> = Vec::new();
+    for c in polymer {
+        if let Some(last) = v.last() {
+            if c.eq_ignore_ascii_case(last)
+                && ((c.is_ascii_uppercase() && last.is_ascii_lowercase())
+                    || (last.is_ascii_uppercase() && c.is_ascii_lowercase()))
+            {
+                // We have aA or Aa.
+                v.pop();
+                continue;
+            }
+        }
+
+        v.push(c);
+    }
+    v.len()
+}
+
+/// Length of the shortest polymer produced by removing a single unit type, along with that
+/// unit type.
+pub fn shortest_without_unit(polymer: &[char]) -> (usize, char) {
+    let alphabet = "abcdefghijklmnopqrstuvwxyz";
+    let mut counts: Vec<(usize, char)> = alphabet
+        .chars()
+        .map(|a| {
+            let len = react(
+                polymer
+                    .iter()
+                    .cloned()
+                    .filter(|c| !c.eq_ignore_ascii_case(&a)),
+            );
+            (len, a)
+        })
+        .collect();
+    counts.sort();
+    counts[0]
+}
+
+pub fn part1(input: &str) -> usize {
+    react(parse(input))
+}
+
+pub fn part2(input: &str) -> usize {
+    shortest_without_unit(&parse(input)).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::input::Source;
+
+    #[test]
+    fn test_react() {
+        assert_eq!(react("aA".chars()), 0);
+        assert_eq!(react("abBA".chars()), 0);
+        assert_eq!(react("abAB".chars()), 4);
+        assert_eq!(react("aabAAB".chars()), 6);
+    }
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1("dabAcCaCBAcCcaDA\n"), 10);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        assert_eq!(part2("dabAcCaCBAcCcaDA\n"), 4);
+        assert_eq!(shortest_without_unit(&parse("dabAcCaCBAcCcaDA")), (4, 'c'));
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Source::from_arg(None, 5).read().unwrap();
+        assert_eq!(part1(&input), 11364);
+        assert_eq!(part2(&input), 4212);
+    }
+}
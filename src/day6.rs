@@ -0,0 +1,211 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-06
+
+//! Day 6: Chronal Coordinates.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use ansi_term::Colour::{Blue, Red};
+
+use crate::error::{parse_lines, Error, ErrorKind, Result};
+use crate::geometry::{Bounds, Coord};
+
+/// Convert input data to coordinates.
+pub fn parse(input: &str) -> Result<Vec<Coord>> {
+    let coords = parse_lines(input, str::parse)?;
+    if coords.is_empty() {
+        return Err(Error::new(ErrorKind::EmptyInput, ""));
+    }
+    Ok(coords)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Cell {
+    Empty,
+    Equal,
+    Occupied(usize),
+    Closest(usize),
+}
+
+fn usize_to_char(v: &usize) -> char {
+    let v = *v as u8;
+    if v < 26 {
+        (v + b'A') as char
+    } else {
+        ((v - 26) + b'a') as char
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cell::Empty => write!(f, " "),
+            Cell::Equal => write!(f, "."),
+            Cell::Occupied(x) => write!(f, "{}", Red.paint(format!("{}", usize_to_char(x)))),
+            Cell::Closest(x) => write!(f, "{}", Blue.paint(format!("{}", usize_to_char(x)))),
+        }
+    }
+}
+
+/// Size of the largest area closest to a single coordinate that isn't infinite.
+pub fn largest_finite_area(coords: &[Coord]) -> usize {
+    let bounds = Bounds::from_coords(coords);
+
+    let mut grid = vec![Cell::Empty; bounds.width() * bounds.height()];
+    // Walk through the coordinates assigning them to the grid.
+    coords.iter().enumerate().for_each(|(e, coord)| {
+        let normalized = bounds.normalize(coord);
+        grid[normalized.y * bounds.width() + normalized.x] = Cell::Occupied(e);
+    });
+
+    // Walk through the grid and for each empty cell determine which coordinate is closest or we
+    // have equal distance.
+    for i in 0..bounds.height() {
+        for j in 0..bounds.width() {
+            if grid[i * bounds.width() + j] != Cell::Empty {
+                continue;
+            }
+
+            let cur = Coord { x: j, y: i };
+            let mut min_dist = bounds.height() + bounds.width();
+
+            for (e, coord) in coords.iter().enumerate() {
+                let dist = cur.manhatten_distance(&bounds.normalize(coord));
+                if dist < min_dist {
+                    grid[i * bounds.width() + j] = Cell::Closest(e);
+                    min_dist = dist;
+                } else if dist == min_dist {
+                    grid[i * bounds.width() + j] = Cell::Equal;
+                }
+            }
+        }
+    }
+
+    // Remove set of coordinates that extend to infinity.
+    let mut infinity_set: HashSet<usize> = HashSet::new();
+    for i in 0..bounds.width() {
+        match grid[i] {
+            Cell::Occupied(x) | Cell::Closest(x) => {
+                infinity_set.insert(x);
+            }
+            _ => {}
+        }
+        match grid[(bounds.height() - 1) * bounds.width() + i] {
+            Cell::Occupied(x) | Cell::Closest(x) => {
+                infinity_set.insert(x);
+            }
+            _ => {}
+        }
+    }
+    for i in 0..bounds.height() {
+        match grid[i * bounds.width()] {
+            Cell::Occupied(x) | Cell::Closest(x) => {
+                infinity_set.insert(x);
+            }
+            _ => {}
+        }
+        match grid[(i * bounds.width()) + bounds.width() - 1] {
+            Cell::Occupied(x) | Cell::Closest(x) => {
+                infinity_set.insert(x);
+            }
+            _ => {}
+        }
+    }
+
+    // Compute the sections with largest area.
+    let mut map: HashMap<usize, usize> = HashMap::new();
+    for v in grid.iter() {
+        match v {
+            Cell::Occupied(id) | Cell::Closest(id) => {
+                if infinity_set.contains(id) {
+                    continue;
+                }
+                *map.entry(*id).or_insert(0) += 1;
+            }
+            _ => {}
+        }
+    }
+
+    map.values().cloned().max().unwrap_or(0)
+}
+
+/// Count the cells whose summed distance to every coordinate is below the threshold.
+///
+/// A cell that lies `d` steps outside of the bounding box is at least `d` away from every
+/// coordinate, so its total distance is at least `coords.len() * d`. The search therefore only
+/// needs to extend `threshold / coords.len()` cells past the bounds.
+pub fn safe_region_size(coords: &[Coord], threshold: usize) -> usize {
+    let bounds = Bounds::from_coords(coords);
+    let margin = (threshold / coords.len()) as isize;
+
+    let min_x = bounds.top_left.x as isize - margin;
+    let max_x = bounds.bottom_right.x as isize + margin;
+    let min_y = bounds.top_left.y as isize - margin;
+    let max_y = bounds.bottom_right.y as isize + margin;
+
+    let mut count = 0;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let total: usize = coords
+                .iter()
+                .map(|c| ((c.x as isize - x).abs() + (c.y as isize - y).abs()) as usize)
+                .sum();
+            if total < threshold {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    Ok(largest_finite_area(&parse(input)?))
+}
+
+/// Size of the region within a total distance of 10000 from every coordinate.
+pub fn part2(input: &str) -> Result<usize> {
+    Ok(safe_region_size(&parse(input)?, 10000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::input::Source;
+
+    const EXAMPLE: &str = "1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9\n";
+
+    #[test]
+    fn test_part1_example() {
+        assert_eq!(part1(EXAMPLE).unwrap(), 17);
+    }
+
+    #[test]
+    fn test_safe_region_example() {
+        assert_eq!(safe_region_size(&parse(EXAMPLE).unwrap(), 32), 16);
+    }
+
+    #[test]
+    fn test_safe_region_outside_bounds() {
+        // A single coordinate's region is a diamond that extends past its one cell bounds.
+        let coords = parse("5, 5\n").unwrap();
+        assert_eq!(safe_region_size(&coords, 1), 1);
+        assert_eq!(safe_region_size(&coords, 3), 13);
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Source::from_arg(None, 6).read().unwrap();
+        assert_eq!(part1(&input).unwrap(), 4011);
+        assert_eq!(part2(&input).unwrap(), 46054);
+    }
+}
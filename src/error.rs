@@ -0,0 +1,159 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-07
+
+//! Errors produced while reading and parsing puzzle input.
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+use std::result;
+use std::str::FromStr;
+
+pub type Result<T> = result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// Input could not be read.
+    Io(io::Error),
+    /// Line does not match the expected format, which is described by the string.
+    Syntax(&'static str),
+    /// Field is not a valid integer.
+    Integer(ParseIntError),
+    /// Timestamp does not name a real date and time.
+    InvalidDate,
+    /// Guard log entry that is not a known behavior.
+    UnknownEvent,
+    /// Configured pattern is not a usable regular expression.
+    Pattern(regex::Error),
+    /// Event that cannot happen at this point in the log, described by the string.
+    Sequence(&'static str),
+    /// Input contains nothing to solve.
+    EmptyInput,
+    /// Values are too large to be combined.
+    Overflow,
+    /// Two values that must be the same length are not, holding both lengths.
+    LengthMismatch(usize, usize),
+    /// Puzzle asks for a single answer but this many are equally valid.
+    Ambiguous(usize),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Io(e) => write!(f, "{}", e),
+            ErrorKind::Syntax(expected) => write!(f, "expected `{}`", expected),
+            ErrorKind::Integer(e) => write!(f, "invalid integer: {}", e),
+            ErrorKind::InvalidDate => write!(f, "invalid date"),
+            ErrorKind::UnknownEvent => write!(f, "unknown event"),
+            ErrorKind::Pattern(e) => write!(f, "invalid pattern: {}", e),
+            ErrorKind::Sequence(problem) => write!(f, "{}", problem),
+            ErrorKind::EmptyInput => write!(f, "no input"),
+            ErrorKind::Overflow => write!(f, "values too large"),
+            ErrorKind::LengthMismatch(a, b) => write!(f, "lengths differ ({} vs {})", a, b),
+            ErrorKind::Ambiguous(n) => write!(f, "{} equally valid answers", n),
+        }
+    }
+}
+
+/// Error along with where in the input it occurred.
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    /// Offending text, empty if the error isn't about a particular piece of input.
+    pub text: String,
+    /// Line number starting from 1.
+    pub line: Option<usize>,
+    pub file: Option<String>,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, text: &str) -> Self {
+        Error {
+            kind,
+            text: text.to_string(),
+            line: None,
+            file: None,
+        }
+    }
+
+    /// Record the line the error occurred on.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Record the file the error occurred in unless one is already known.
+    pub fn in_file<F: fmt::Display>(mut self, file: F) -> Self {
+        if self.file.is_none() {
+            self.file = Some(file.to_string());
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "{}:", line)?;
+        }
+        if self.file.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", self.kind)?;
+        if !self.text.is_empty() {
+            write!(f, ": '{}'", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Io(e) => Some(e),
+            ErrorKind::Integer(e) => Some(e),
+            ErrorKind::Pattern(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::new(ErrorKind::Io(e), "")
+    }
+}
+
+/// Parse an integer field of a line.
+pub(crate) fn parse_int<T>(field: &str, line: &str) -> Result<T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    field
+        .parse::<T>()
+        .map_err(|e| Error::new(ErrorKind::Integer(e), line))
+}
+
+/// Parse every line of the input, attaching the line number to any error.
+pub(crate) fn parse_lines<T, F>(input: &str, f: F) -> Result<Vec<T>>
+where
+    F: Fn(&str) -> Result<T>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
@@ -0,0 +1,137 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-07
+
+//! Grid coordinates and bounding boxes shared between the days.
+
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::{parse_int, Error, ErrorKind, Result};
+
+lazy_static! {
+    // Line is: 181, 184
+    static ref RE: Regex =
+        Regex::new(r"^(\d+), (\d+)$").unwrap();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Coord {
+    pub fn new(x: usize, y: usize) -> Self {
+        Coord { x, y }
+    }
+
+    pub fn manhatten_distance(&self, other: &Coord) -> usize {
+        let x1 = self.x as isize;
+        let y1 = self.y as isize;
+        let x2 = other.x as isize;
+        let y2 = other.y as isize;
+        (isize::abs(x1 - x2) + isize::abs(y1 - y2)) as usize
+    }
+}
+
+impl FromStr for Coord {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let caps = RE
+            .captures(line)
+            .ok_or_else(|| Error::new(ErrorKind::Syntax("x, y"), line))?;
+        let x = parse_int(&caps[1], line)?;
+        let y = parse_int(&caps[2], line)?;
+        Ok(Coord { x, y })
+    }
+}
+
+/// Inclusive rectangle described by its top left and bottom right corners.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub top_left: Coord,
+    pub bottom_right: Coord,
+}
+
+impl Bounds {
+    pub fn new(x1: usize, y1: usize, x2: usize, y2: usize) -> Self {
+        Bounds {
+            top_left: Coord { x: x1, y: y1 },
+            bottom_right: Coord { x: x2, y: y2 },
+        }
+    }
+
+    /// Compute the maximum grid size required to represent all coordinates.
+    pub fn from_coords(coords: &[Coord]) -> Self {
+        let min_x = coords.iter().map(|c| c.x).min().unwrap();
+        let max_x = coords.iter().map(|c| c.x).max().unwrap();
+        let min_y = coords.iter().map(|c| c.y).min().unwrap();
+        let max_y = coords.iter().map(|c| c.y).max().unwrap();
+
+        Bounds {
+            top_left: Coord { x: min_x, y: min_y },
+            bottom_right: Coord { x: max_x, y: max_y },
+        }
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.top_left.x
+            && x <= self.bottom_right.x
+            && y >= self.top_left.y
+            && y <= self.bottom_right.y
+    }
+
+    /// Rectangle covered by both bounds, if they overlap at all.
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        let top_left = Coord {
+            x: self.top_left.x.max(other.top_left.x),
+            y: self.top_left.y.max(other.top_left.y),
+        };
+        let bottom_right = Coord {
+            x: self.bottom_right.x.min(other.bottom_right.x),
+            y: self.bottom_right.y.min(other.bottom_right.y),
+        };
+        if top_left.x <= bottom_right.x && top_left.y <= bottom_right.y {
+            Some(Bounds {
+                top_left,
+                bottom_right,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Return the width of the bounds.
+    pub fn width(&self) -> usize {
+        self.bottom_right.x - self.top_left.x + 1
+    }
+
+    /// Return the height of the bounds.
+    pub fn height(&self) -> usize {
+        self.bottom_right.y - self.top_left.y + 1
+    }
+
+    /// Return the number of cells within the bounds.
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    /// Translate the coordinate so that it is relative to the top left of the bounds.
+    pub fn normalize(&self, coord: &Coord) -> Coord {
+        Coord {
+            x: coord.x - self.top_left.x,
+            y: coord.y - self.top_left.y,
+        }
+    }
+}
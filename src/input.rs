@@ -0,0 +1,69 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-07
+
+//! Locating and reading puzzle input.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Where puzzle input is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// Interpret a command line argument as an input source.
+    ///
+    /// `-` selects stdin and no argument selects the checked in input for the day.
+    pub fn from_arg(arg: Option<&str>, day: usize) -> Self {
+        match arg {
+            Some("-") => Source::Stdin,
+            Some(path) => Source::File(PathBuf::from(path)),
+            None => Source::File(default_path(day)),
+        }
+    }
+
+    /// Read the entire input.
+    pub fn read(&self) -> Result<String> {
+        let input = match self {
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map(|_| input)
+            }
+            Source::File(path) => fs::read_to_string(path),
+        };
+        input.map_err(|e| Error::from(e).in_file(self))
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Path of the checked in input for the given day.
+///
+/// The path is anchored at the crate root so that binaries may be run from any directory.
+pub fn default_path(day: usize) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("input")
+        .join(format!("day{}.txt", day))
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 by Brandon Edens.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+//
+// Author: Brandon Edens <brandonedens@gmail.com>
+// Date: 2018-12-07
+
+//! Solutions to the Advent of Code 2018 puzzles.
+//!
+//! Every day module exposes a `parse` function that converts the puzzle input into its model
+//! along with `part1` and `part2` functions that take the raw puzzle input and return the answer.
+//! Malformed input is reported through [`Error`] rather than a panic.
+
+pub mod cli;
+pub mod error;
+pub mod geometry;
+pub mod input;
+
+pub use crate::error::{Error, ErrorKind, Result};
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;